@@ -0,0 +1,289 @@
+use bevy::{
+    prelude::{
+        Component, Entity, Event, EventWriter, Query, Res, ResMut, Resource, Transform, Vec3,
+    },
+    utils::{HashMap, HashSet},
+};
+
+use crate::{
+    boids::{BoidCollisionGroup, BoidSchool},
+    spatial::{BoidSpatialIndex, IndexedBoid},
+};
+
+/// Per flock statistics about the boids, updated every simulation tick.
+///
+/// Boids are counted together when they share a [Flock](crate::boids::Flock), a [BoidSchool]
+/// and collision groups, see [FlockKey].
+///
+/// ```rust
+/// fn school_positions(analytics: Res<FlockAnalytics>) {
+///     for (key, stats) in analytics.iter() {
+///         if key.school.is_some() {
+///             info!("{} boids around {}", stats.count, stats.centroid);
+///         }
+///     }
+/// }
+/// ```
+#[derive(Resource, Default)]
+pub struct FlockAnalytics {
+    /// How close two boids have to be to count as part of the same sub flock. When this is
+    /// `None`, the largest view range of any boid is used.
+    pub link_distance: Option<f32>,
+    flocks: HashMap<FlockKey, FlockStats>,
+}
+
+impl FlockAnalytics {
+    /// Gets the statistics for the given flock, if it has any boids
+    pub fn get(&self, key: FlockKey) -> Option<&FlockStats> {
+        self.flocks.get(&key)
+    }
+
+    /// Iterates over every flock that has boids in it
+    pub fn iter(&self) -> impl Iterator<Item = (FlockKey, &FlockStats)> {
+        self.flocks.iter().map(|(key, stats)| (*key, stats))
+    }
+}
+
+/// Which boids [FlockAnalytics] counts together.
+///
+/// Boids without a [BoidCollisionGroup] are counted under [BoidCollisionGroup::ALL], and a boid
+/// that is in several groups is counted under that exact combination of groups.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct FlockKey {
+    /// The entity with the boids' [Flock](crate::boids::Flock) on it
+    pub flock: Option<Entity>,
+    pub school: Option<BoidSchool>,
+    pub collision_group: BoidCollisionGroup,
+}
+
+impl FlockKey {
+    pub(crate) fn of(boid: &IndexedBoid) -> Self {
+        Self {
+            flock: boid.flock,
+            school: boid.school,
+            collision_group: boid.collision_group.unwrap_or(BoidCollisionGroup::ALL),
+        }
+    }
+}
+
+/// Statistics about all the boids in one flock.
+#[derive(Clone, Debug, Default)]
+pub struct FlockStats {
+    /// The number of boids in the flock
+    pub count: usize,
+    /// The average position of the boids
+    pub centroid: Vec3,
+    /// The corner of the bounding box with the lowest coordinates
+    pub min: Vec3,
+    /// The corner of the bounding box with the highest coordinates
+    pub max: Vec3,
+    /// The average velocity of the boids
+    pub mean_velocity: Vec3,
+    /// The separate clumps of boids in the flock, largest first. When the flock hasn't split
+    /// up there is just one of these, covering the whole flock.
+    pub sub_flocks: Vec<SubFlock>,
+}
+
+impl FlockStats {
+    /// Returns true if the boids in this flock have split into more than one clump
+    pub fn is_split(&self) -> bool {
+        self.sub_flocks.len() > 1
+    }
+}
+
+/// A clump of boids within a flock, where every boid is within the link distance of at least
+/// one other boid in the clump.
+#[derive(Clone, Debug, Default)]
+pub struct SubFlock {
+    pub count: usize,
+    pub centroid: Vec3,
+    pub mean_velocity: Vec3,
+}
+
+/// Add this to an entity to get [FlockEntered] and [FlockExited] events when the centroid of a
+/// flock moves within or out of `radius` of it.
+#[derive(Component)]
+pub struct FlockWatcher {
+    pub radius: f32,
+    /// The collision groups to watch. A flock is watched if its collision group shares any bit
+    /// with this mask.
+    pub groups: BoidCollisionGroup,
+    inside: HashSet<FlockKey>,
+}
+
+impl FlockWatcher {
+    pub fn new(radius: f32, groups: BoidCollisionGroup) -> Self {
+        Self {
+            radius,
+            groups,
+            inside: HashSet::default(),
+        }
+    }
+
+    /// Returns true if the given flock was within range as of the last simulation tick
+    pub fn is_inside(&self, flock: FlockKey) -> bool {
+        self.inside.contains(&flock)
+    }
+}
+
+/// Sent when the centroid of a flock comes within range of a [FlockWatcher].
+#[derive(Event, Debug, Clone, Copy)]
+pub struct FlockEntered {
+    pub watcher: Entity,
+    pub flock: FlockKey,
+    pub distance: f32,
+}
+
+/// Sent when the centroid of a flock leaves the range of a [FlockWatcher], or the flock has no
+/// boids left.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct FlockExited {
+    pub watcher: Entity,
+    pub flock: FlockKey,
+}
+
+pub(crate) fn update_flock_analytics(
+    index: Res<BoidSpatialIndex>,
+    mut analytics: ResMut<FlockAnalytics>,
+) {
+    let boids = index.boids();
+    let link_distance = analytics.link_distance.unwrap_or(index.cell_size());
+
+    let mut members: HashMap<FlockKey, Vec<usize>> = HashMap::default();
+    for (idx, boid) in boids.iter().enumerate() {
+        members.entry(FlockKey::of(boid)).or_default().push(idx);
+    }
+
+    analytics.flocks.clear();
+
+    for (key, indices) in members {
+        let mut stats = FlockStats {
+            count: indices.len(),
+            min: Vec3::splat(f32::MAX),
+            max: Vec3::splat(f32::MIN),
+            ..Default::default()
+        };
+
+        for &idx in &indices {
+            let boid = &boids[idx];
+            stats.centroid += boid.position;
+            stats.mean_velocity += boid.velocity;
+            stats.min = stats.min.min(boid.position);
+            stats.max = stats.max.max(boid.position);
+        }
+
+        stats.centroid /= stats.count as f32;
+        stats.mean_velocity /= stats.count as f32;
+        stats.sub_flocks = find_sub_flocks(&index, key, &indices, link_distance);
+
+        analytics.flocks.insert(key, stats);
+    }
+}
+
+/// Splits the boids at `indices` into connected clumps with a union find over the spatial index.
+fn find_sub_flocks(
+    index: &BoidSpatialIndex,
+    key: FlockKey,
+    indices: &[usize],
+    link_distance: f32,
+) -> Vec<SubFlock> {
+    let boids = index.boids();
+
+    // union find keyed by position in the spatial index
+    let mut parents: HashMap<usize, usize> = indices.iter().map(|idx| (*idx, *idx)).collect();
+
+    fn find(parents: &mut HashMap<usize, usize>, idx: usize) -> usize {
+        let mut root = idx;
+        while parents[&root] != root {
+            root = parents[&root];
+        }
+
+        // flatten the path so later lookups are quick
+        let mut current = idx;
+        while parents[&current] != root {
+            let next = parents[&current];
+            parents.insert(current, root);
+            current = next;
+        }
+
+        root
+    }
+
+    for &idx in indices {
+        let position = boids[idx].position;
+
+        for other in index.candidates(position, link_distance) {
+            if other <= idx
+                || FlockKey::of(&boids[other]) != key
+                || boids[other].position.distance(position) > link_distance
+            {
+                continue;
+            }
+
+            let (a, b) = (find(&mut parents, idx), find(&mut parents, other));
+            if a != b {
+                parents.insert(a.max(b), a.min(b));
+            }
+        }
+    }
+
+    let mut sub_flocks: HashMap<usize, SubFlock> = HashMap::default();
+    for &idx in indices {
+        let root = find(&mut parents, idx);
+        let sub_flock = sub_flocks.entry(root).or_default();
+        sub_flock.count += 1;
+        sub_flock.centroid += boids[idx].position;
+        sub_flock.mean_velocity += boids[idx].velocity;
+    }
+
+    let mut sub_flocks = sub_flocks
+        .into_values()
+        .map(|mut sub_flock| {
+            sub_flock.centroid /= sub_flock.count as f32;
+            sub_flock.mean_velocity /= sub_flock.count as f32;
+            sub_flock
+        })
+        .collect::<Vec<_>>();
+    sub_flocks.sort_by_key(|sub_flock| std::cmp::Reverse(sub_flock.count));
+    sub_flocks
+}
+
+pub(crate) fn update_flock_watchers(
+    analytics: Res<FlockAnalytics>,
+    mut watchers: Query<(Entity, &mut FlockWatcher, &Transform)>,
+    mut entered: EventWriter<FlockEntered>,
+    mut exited: EventWriter<FlockExited>,
+) {
+    for (watcher, mut flock_watcher, transform) in &mut watchers {
+        let mut now_inside = HashSet::default();
+
+        for (key, stats) in analytics.iter() {
+            if !flock_watcher.groups.intersects(key.collision_group) {
+                continue;
+            }
+
+            let distance = stats.centroid.distance(transform.translation);
+            if distance > flock_watcher.radius {
+                continue;
+            }
+
+            now_inside.insert(key);
+            if !flock_watcher.inside.contains(&key) {
+                entered.send(FlockEntered {
+                    watcher,
+                    flock: key,
+                    distance,
+                });
+            }
+        }
+
+        for key in flock_watcher.inside.difference(&now_inside) {
+            exited.send(FlockExited {
+                watcher,
+                flock: *key,
+            });
+        }
+
+        flock_watcher.inside = now_inside;
+    }
+}
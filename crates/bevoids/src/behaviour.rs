@@ -0,0 +1,144 @@
+use bevy::{
+    prelude::{App, Entity, FixedUpdate, IntoSystemConfigs, Res, ResMut, Resource, Vec3},
+    tasks::{ComputeTaskPool, ParallelSlice, TaskPool},
+};
+
+use crate::{
+    boids::BoidSet,
+    spatial::{BoidSpatialIndex, IndexedBoid},
+};
+
+/// A custom steering behaviour, added with [BoidBehaviourAppExt::add_boid_behaviour].
+///
+/// Every simulation tick, [BoidBehaviour::steer] is called once for each boid, and the force it
+/// returns is multiplied by [BoidBehaviour::weight] and added to the boid's other steering
+/// forces. Like the built in forces, it's the change in velocity over one frame at 60fps.
+///
+/// Behaviours are resources, so anything they need from the world can be kept up to date by your
+/// own systems running before [BoidSet::Simulation].
+///
+/// ```rust
+/// #[derive(Resource)]
+/// struct AvoidPlayer {
+///     position: Vec3,
+/// }
+///
+/// impl BoidBehaviour for AvoidPlayer {
+///     fn steer(&self, context: &BoidContext) -> Vec3 {
+///         let away = context.boid().position - self.position;
+///         if away.length() < 100.0 {
+///             away.normalize_or_zero()
+///         } else {
+///             Vec3::ZERO
+///         }
+///     }
+///
+///     fn weight(&self) -> f32 {
+///         2.0
+///     }
+/// }
+///
+/// app.add_boid_behaviour(AvoidPlayer { position: Vec3::ZERO });
+/// ```
+pub trait BoidBehaviour: Resource {
+    /// Works out the steering force for a single boid
+    fn steer(&self, context: &BoidContext) -> Vec3;
+
+    /// How much to scale the steering force by
+    fn weight(&self) -> f32 {
+        1.0
+    }
+}
+
+/// What a [BoidBehaviour] gets to know about the boid it's steering.
+pub struct BoidContext<'a> {
+    index: &'a BoidSpatialIndex,
+    idx: usize,
+}
+
+impl<'a> BoidContext<'a> {
+    /// The boid being steered, as of the start of this tick
+    pub fn boid(&self) -> &'a IndexedBoid {
+        &self.index.boids()[self.idx]
+    }
+
+    /// The entity of the boid being steered
+    pub fn entity(&self) -> Entity {
+        self.boid().entity
+    }
+
+    /// The neighbours the boid can see this tick, i.e. the same boids it flocks with
+    pub fn neighbours(&self) -> impl Iterator<Item = &'a IndexedBoid> + 'a {
+        let boids = self.index.boids();
+        self.index
+            .neighbours(self.idx)
+            .iter()
+            .map(move |idx| &boids[*idx])
+    }
+
+    /// The spatial index, for behaviours that need to look further than the boid can see
+    pub fn index(&self) -> &'a BoidSpatialIndex {
+        self.index
+    }
+}
+
+/// Registers custom boid behaviours on an [App].
+pub trait BoidBehaviourAppExt {
+    /// Adds a [BoidBehaviour] that steers every boid. The behaviour is inserted as a resource,
+    /// so it can be changed or replaced later on.
+    fn add_boid_behaviour<B: BoidBehaviour>(&mut self, behaviour: B) -> &mut Self;
+}
+
+impl BoidBehaviourAppExt for App {
+    fn add_boid_behaviour<B: BoidBehaviour>(&mut self, behaviour: B) -> &mut Self {
+        self.insert_resource(behaviour).add_systems(
+            FixedUpdate,
+            apply_boid_behaviour::<B>.in_set(BoidSet::Behaviours),
+        )
+    }
+}
+
+/// The sum of all custom behaviour forces for each boid in the [BoidSpatialIndex], by index.
+#[derive(Resource, Default)]
+pub(crate) struct BoidBehaviourForces(pub(crate) Vec<Vec3>);
+
+pub(crate) fn reset_boid_behaviour_forces(
+    index: Res<BoidSpatialIndex>,
+    mut forces: ResMut<BoidBehaviourForces>,
+) {
+    forces.0.clear();
+    forces.0.resize(index.boids().len(), Vec3::ZERO);
+}
+
+fn apply_boid_behaviour<B: BoidBehaviour>(
+    behaviour: Res<B>,
+    index: Res<BoidSpatialIndex>,
+    mut forces: ResMut<BoidBehaviourForces>,
+) {
+    let weight = behaviour.weight();
+    let task_pool = ComputeTaskPool::get_or_init(TaskPool::default);
+
+    let indices = (0..forces.0.len()).collect::<Vec<_>>();
+    let steering = indices.par_splat_map(task_pool, None, |_, chunk| {
+        chunk
+            .iter()
+            .map(|idx| {
+                let context = BoidContext {
+                    index: &index,
+                    idx: *idx,
+                };
+
+                // the force would be thrown away for boids that aren't steered this tick
+                if context.boid().steer_ticks == 0 {
+                    return Vec3::ZERO;
+                }
+
+                context.boid().space.project(behaviour.steer(&context)) * weight
+            })
+            .collect::<Vec<_>>()
+    });
+
+    for (force, steering) in forces.0.iter_mut().zip(steering.into_iter().flatten()) {
+        *force += steering;
+    }
+}
@@ -1,4 +1,19 @@
-use bevy::prelude::{Component, Plugin, Resource, Update, Vec3};
+use bevy::prelude::{
+    AssetApp, Component, Entity, FixedUpdate, IntoSystemConfigs, IntoSystemSetConfigs, Plugin,
+    Quat, Reflect, ReflectComponent, Resource, SystemSet, Update, Vec2, Vec3,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    analytics::{self, FlockAnalytics, FlockEntered, FlockExited},
+    behaviour::{self, BoidBehaviourForces},
+    flow::FlowField,
+    leader::{self, BoidLeader, BoidLeaderElected, BoidLeaders},
+    obstacle::BoidObstacle,
+    preset::{self, BoidPreset, BoidPresetLoader},
+    spatial::{BoidSpatialIndex, IndexedBoid},
+};
 
 /// The plugin you have to add to use boids.
 ///
@@ -7,39 +22,340 @@ pub struct BoidsPlugin;
 
 impl Plugin for BoidsPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.add_systems(
-            Update,
-            (
-                systems::handle_boid_gizmos,
-                systems::handle_boid_movement,
-                systems::handle_boid_orientation,
-            ),
-        );
+        app.init_resource::<BoidsConfig>()
+            .init_resource::<BoidRng>()
+            .init_resource::<BoidSpatialIndex>()
+            .init_resource::<FlockAnalytics>()
+            .init_resource::<BoidBehaviourForces>()
+            .init_resource::<BoidLeaders>()
+            .add_event::<BoidLeaderElected>()
+            .add_event::<FlockEntered>()
+            .add_event::<FlockExited>()
+            .init_asset::<BoidPreset>()
+            .init_asset_loader::<BoidPresetLoader>()
+            .configure_sets(FixedUpdate, BoidSet::Behaviours.in_set(BoidSet::Simulation))
+            .add_systems(
+                FixedUpdate,
+                (
+                    (
+                        systems::sync_boid_positions,
+                        systems::rebuild_spatial_index,
+                        leader::elect_boid_leaders,
+                        analytics::update_flock_analytics,
+                        analytics::update_flock_watchers,
+                        systems::track_predators,
+                        systems::advance_group_goals,
+                        behaviour::reset_boid_behaviour_forces,
+                    )
+                        .chain()
+                        .before(BoidSet::Behaviours),
+                    systems::handle_boid_movement.after(BoidSet::Behaviours),
+                )
+                    .in_set(BoidSet::Simulation),
+            )
+            .add_systems(
+                Update,
+                (
+                    systems::interpolate_boid_transforms,
+                    systems::handle_boid_orientation,
+                )
+                    .chain()
+                    .in_set(BoidSet::Interpolation),
+            )
+            .add_systems(Update, preset::apply_boid_presets)
+            .add_systems(Update, systems::handle_boid_gizmos)
+            .register_type::<BoidDebug>();
     }
 }
 
+/// The system sets the boid plugin runs in, so you can order your own systems against them.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BoidSet {
+    /// Steers and moves the boids. Runs in [FixedUpdate], so the simulation doesn't depend on
+    /// the frame rate.
+    Simulation,
+    /// Works out the forces from custom [BoidBehaviour](crate::behaviour::BoidBehaviour)s. Runs
+    /// inside [BoidSet::Simulation], after the neighbours are found and before boids move.
+    Behaviours,
+    /// Moves boid transforms between the last two simulation ticks and turns them to face
+    /// where they're going. Runs in [Update].
+    Interpolation,
+}
+
+/// Steering forces are tuned as the change in velocity over one frame at this rate, so values
+/// tuned before the simulation moved to a fixed timestep behave the same.
+const STEERING_REFERENCE_RATE: f32 = 60.0;
+
+/// How quickly boids roll towards their bank angle, per second
+const BANK_RESPONSE: f32 = 5.0;
+
 /// The configuration resource of the boids.
 ///
-/// This will be moved to a component later on
-#[derive(Resource)]
+/// This only applies to boids that aren't part of a [Flock], which carries its own configuration.
+#[derive(Resource, Default)]
 pub struct BoidsConfig {
     pub space: BoidSpace,
     pub debug: bool,
+    pub lod: Option<BoidLod>,
+}
+
+/// Debug overlays for a single entity, drawn with gizmos.
+///
+/// Add it to a boid to see what it's reacting to, or to a [BoidRepulsor] or [BoidObstacle] to see
+/// its range or shape. A repulsor's range is drawn in the space of the [Flock] it's linked to with
+/// [BoidFlock], if any. The view range is also drawn for every boid when `debug` is set on its
+/// [Flock] or the [BoidsConfig].
+#[derive(Component, Reflect, Clone)]
+#[reflect(Component)]
+pub struct BoidDebug {
+    /// Draw the field of view out to the view range
+    pub view_range: bool,
+    /// Draw the range other boids are pushed away from
+    pub protected_range: bool,
+    /// Draw the boid's velocity
+    pub velocity: bool,
+    /// Draw each steering force from the last simulation tick
+    pub forces: bool,
+    /// Draw a line to every neighbour the boid saw in the last simulation tick
+    pub neighbours: bool,
+    /// Draw the range of a [BoidRepulsor]
+    pub repulsor_range: bool,
+    /// Draw the shape of a [BoidObstacle]
+    pub obstacle_shape: bool,
+    /// How much to scale steering forces by when drawing them
+    pub force_scale: f32,
+    #[reflect(ignore)]
+    last_forces: BoidForces,
+    #[reflect(ignore)]
+    last_neighbours: Vec<Vec3>,
+}
+
+impl BoidDebug {
+    /// Turns on every overlay
+    pub fn all() -> Self {
+        Self {
+            view_range: true,
+            protected_range: true,
+            velocity: true,
+            forces: true,
+            neighbours: true,
+            repulsor_range: true,
+            obstacle_shape: true,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for BoidDebug {
+    fn default() -> Self {
+        Self {
+            view_range: false,
+            protected_range: false,
+            velocity: false,
+            forces: false,
+            neighbours: false,
+            repulsor_range: false,
+            obstacle_shape: false,
+            force_scale: 10.0,
+            last_forces: BoidForces::default(),
+            last_neighbours: Vec::new(),
+        }
+    }
+}
+
+/// The steering forces acting on a boid, recorded for [BoidDebug]
+#[derive(Clone, Copy, Default)]
+struct BoidForces {
+    coherence: Vec3,
+    separation: Vec3,
+    alignment: Vec3,
+    border: Vec3,
+    repulsor: Vec3,
+    obstacle: Vec3,
+    jitter: Vec3,
+    custom: Vec3,
+    flee: Vec3,
+    attractor: Vec3,
+    goal: Vec3,
+}
+
+/// A flock of boids that share the same space, debug settings and default tuning.
+///
+/// Spawn this on its own entity, and link boids to it with [BoidFlock]. Boids only
+/// flock with other boids in the same flock, so you can run e.g. 2d fish and 3d birds
+/// side by side with different settings.
+///
+/// ## Example
+/// ```rust
+/// let flock = Flock::new(
+///    BoidSpace::TwoDimensional,
+///    BoidSpeed::new(BOID_MIN_SPEED, BOID_MAX_SPEED),
+///    BoidTurningStrength::new(
+///       BOID_COHESION,
+///       BOID_SEPARATION,
+///       BOID_ALIGNMENT,
+///       BOID_BORDER_TURN_STRENGTH,
+///    ),
+///    BoidViewConfig::new(BOID_FOV, BOID_PROTECTED_RANGE, BOID_VIEW_RANGE),
+/// );
+///
+/// let boid = flock.boid();
+/// let flock_entity = commands.spawn(flock).id();
+///
+/// commands.spawn((transform, boid, BoidFlock(flock_entity)));
+/// ```
+#[derive(Component, Clone, Copy)]
+pub struct Flock {
+    pub space: BoidSpace,
+    pub debug: bool,
+    /// The default speed limits for boids in this flock
+    pub speed: BoidSpeed,
+    /// The default turning strength for boids in this flock
+    pub turning_strength: BoidTurningStrength,
+    /// The default view configuration for boids in this flock
+    pub view_config: BoidViewConfig,
+    /// The default agility for boids in this flock
+    pub agility: BoidAgility,
+    /// How much effort goes into steering boids far from a [BoidLodFocus]. Every boid is
+    /// steered every tick if this is `None`.
+    pub lod: Option<BoidLod>,
+}
+
+impl Flock {
+    pub fn new(
+        space: BoidSpace,
+        speed: BoidSpeed,
+        turning_strength: BoidTurningStrength,
+        view_config: BoidViewConfig,
+    ) -> Self {
+        Self {
+            space,
+            debug: false,
+            speed,
+            turning_strength,
+            view_config,
+            agility: BoidAgility::default(),
+            lod: None,
+        }
+    }
+
+    pub fn with_lod(mut self, lod: BoidLod) -> Self {
+        self.lod = Some(lod);
+        self
+    }
+
+    pub fn with_agility(mut self, agility: BoidAgility) -> Self {
+        self.agility = agility;
+        self
+    }
+
+    pub fn with_debug(mut self, debug: bool) -> Self {
+        self.debug = debug;
+        self
+    }
+
+    /// Creates a new boid using this flock's default tuning
+    pub fn boid(&self) -> Boid {
+        Boid::new(self.speed, self.turning_strength, self.view_config).with_agility(self.agility)
+    }
 }
 
+/// Links a boid to the [Flock] on the given entity.
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub struct BoidFlock(pub Entity);
+
+/// Splits the boids of a [Flock] into separate schools. [FlockAnalytics] and [BoidLeader]s are
+/// kept per school, so schools that share a flock and collision groups aren't lumped together.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct BoidSchool(pub u32);
+
+/// Level of detail tiers, so boids nobody is looking at are cheaper to simulate.
+///
+/// Boids within `full_range` of the nearest [BoidLodFocus] are steered every tick. Out to
+/// `reduced_range` they're steered every `reduced_interval` ticks, catching up on the missed
+/// steering all at once. Further out they just keep going in a straight line. Boids that
+/// aren't steered in a tick are still moved, and skip the neighbour search entirely.
+///
+/// If there is no [BoidLodFocus], every boid gets a full update.
+#[derive(Clone, Copy, Debug)]
+pub struct BoidLod {
+    pub full_range: f32,
+    pub reduced_range: f32,
+    pub reduced_interval: u32,
+}
+
+impl BoidLod {
+    pub fn new(full_range: f32, reduced_range: f32, reduced_interval: u32) -> Self {
+        Self {
+            full_range,
+            reduced_range,
+            reduced_interval,
+        }
+    }
+
+    /// Gets how many ticks of steering a boid should catch up on this tick, 0 meaning it just
+    /// keeps going. `stagger` spreads out which tick each boid gets its reduced update on.
+    pub(crate) fn steer_ticks(
+        &self,
+        position: Vec3,
+        focuses: &[Vec3],
+        tick: u32,
+        stagger: u32,
+    ) -> u32 {
+        let Some(distance) = focuses
+            .iter()
+            .map(|focus| focus.distance(position))
+            .min_by(f32::total_cmp)
+        else {
+            return 1;
+        };
+
+        let interval = self.reduced_interval.max(1);
+
+        if distance <= self.full_range {
+            1
+        } else if distance <= self.reduced_range {
+            if tick.wrapping_add(stagger).is_multiple_of(interval) {
+                interval
+            } else {
+                0
+            }
+        } else {
+            0
+        }
+    }
+}
+
+/// Marks a point of interest for [BoidLod], e.g. the camera or the player. Boids near any focus
+/// get a full update.
+#[derive(Component, Clone, Copy, Default)]
+pub struct BoidLodFocus;
+
 /// Whether the boids are in 3d or 2d space
+#[derive(Clone, Copy, Default)]
 pub enum BoidSpace {
+    #[default]
     TwoDimensional,
     ThreeDimensional,
 }
 
+impl BoidSpace {
+    /// Projects a vector into this space, i.e. drops the z component in 2d
+    pub(crate) fn project(&self, vector: Vec3) -> Vec3 {
+        match self {
+            BoidSpace::TwoDimensional => vector.truncate().extend(0.0),
+            BoidSpace::ThreeDimensional => vector,
+        }
+    }
+}
+
 /// Represents the turning strength for the different parts that make up the boid's
 /// movement.
 ///
 /// These parameters are very finnicky, so you might need to tweak a lot.
 /// Refer to [this](https://en.wikipedia.org/wiki/Boids) to understand what
 /// `coherence`, `separation` and `alignment` does.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct BoidTurningStrength {
     /// How strongly the boid steers towards the center of mass of flock.
     pub coherence: f32,
@@ -63,11 +379,10 @@ impl BoidTurningStrength {
 }
 
 /// Represents the different options for view configuration of a boid
-///
-/// **NOTE**: FOV isn't currently implemented.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct BoidViewConfig {
-    /// The field of view of a boid.
+    /// The field of view of a boid in degrees, centred on the direction the boid is moving.
+    /// Neighbours outside of this cone are ignored. Use 360 to see all around.
     pub fov: u32,
     /// The range that causes a boid to avoid another boid.
     /// Shouldn't be larger than `view_range`
@@ -75,6 +390,10 @@ pub struct BoidViewConfig {
     /// How far a boid can see.
     /// Shouldn't be smaller than `protected_range`
     pub view_range: f32,
+    /// When set, a boid only reacts to this many of the nearest boids it can see, instead of
+    /// every boid in view. This keeps dense flocks from collapsing and sparse ones together.
+    #[serde(default)]
+    pub nearest: Option<usize>,
 }
 
 impl BoidViewConfig {
@@ -83,12 +402,39 @@ impl BoidViewConfig {
             fov,
             protected_range,
             view_range,
+            nearest: None,
         }
     }
+
+    /// Only react to the `nearest` closest boids in view, rather than all of them
+    pub fn with_nearest(mut self, nearest: usize) -> Self {
+        self.nearest = Some(nearest);
+        self
+    }
+
+    /// Returns true if something at `offset` from the boid is inside the boid's field of view,
+    /// when the boid is moving in the direction of `heading`.
+    ///
+    /// Boids that aren't moving can see in every direction.
+    pub fn in_fov(&self, heading: Vec3, offset: Vec3) -> bool {
+        if self.fov >= 360 {
+            return true;
+        }
+
+        match (heading.try_normalize(), offset.try_normalize()) {
+            (Some(heading), Some(offset)) => heading.dot(offset) >= self.half_fov().cos(),
+            _ => true,
+        }
+    }
+
+    /// Half of the field of view, in radians
+    fn half_fov(&self) -> f32 {
+        (self.fov.min(360) as f32 / 2.0).to_radians()
+    }
 }
 
 /// Represents the min/max speed limits of a boid
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct BoidSpeed {
     min: f32,
     max: f32,
@@ -100,23 +446,458 @@ impl BoidSpeed {
     }
 }
 
+/// Limits on how quickly a boid can change its velocity, and how far it leans into turns.
+///
+/// By default there are no limits, so a boid can snap to any heading in a single tick.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct BoidAgility {
+    /// The fastest the boid can turn, in radians per second
+    #[serde(default)]
+    pub max_turn_rate: Option<f32>,
+    /// The most the boid's velocity can change by per second
+    #[serde(default)]
+    pub max_acceleration: Option<f32>,
+    /// How far the boid rolls into turns in 3d, in radians per unit of sideways acceleration
+    #[serde(default)]
+    pub bank: f32,
+    /// The furthest the boid can roll, in radians
+    #[serde(default)]
+    pub max_bank: f32,
+}
+
+impl BoidAgility {
+    pub fn new(max_turn_rate: Option<f32>, max_acceleration: Option<f32>) -> Self {
+        Self {
+            max_turn_rate,
+            max_acceleration,
+            ..Default::default()
+        }
+    }
+
+    /// Makes 3d boids roll into turns, by `bank` radians per unit of sideways acceleration and
+    /// at most `max_bank` radians
+    pub fn with_bank(mut self, bank: f32, max_bank: f32) -> Self {
+        self.bank = bank;
+        self.max_bank = max_bank;
+        self
+    }
+
+    /// Limits the change from `velocity` to `new_velocity` over `delta_seconds`. Turns stay in
+    /// the plane of a 2d `space`.
+    fn limit(
+        &self,
+        velocity: Vec3,
+        new_velocity: Vec3,
+        delta_seconds: f32,
+        space: BoidSpace,
+    ) -> Vec3 {
+        let mut new_velocity = new_velocity;
+
+        if let Some(max_acceleration) = self.max_acceleration {
+            new_velocity = velocity
+                + (new_velocity - velocity).clamp_length_max(max_acceleration * delta_seconds);
+        }
+
+        if let Some(max_turn_rate) = self.max_turn_rate {
+            if let (Some(from), Some(to)) = (velocity.try_normalize(), new_velocity.try_normalize())
+            {
+                let angle = from.angle_between(to);
+                let max_angle = max_turn_rate * delta_seconds;
+
+                if angle > max_angle {
+                    // a u-turn could go either way, so pick an axis that keeps 2d boids flat
+                    let axis = match space {
+                        BoidSpace::TwoDimensional => Vec3::Z * from.cross(to).z.signum(),
+                        BoidSpace::ThreeDimensional => from
+                            .cross(to)
+                            .try_normalize()
+                            .unwrap_or_else(|| from.any_orthonormal_vector()),
+                    };
+                    new_velocity =
+                        Quat::from_axis_angle(axis, max_angle) * from * new_velocity.length();
+                }
+            }
+        }
+
+        new_velocity
+    }
+
+    /// Gets the roll angle to lean into a turn, from the sideways part of `acceleration`
+    fn calc_bank(&self, velocity: Vec3, acceleration: Vec3) -> f32 {
+        let Some(right) = velocity.cross(Vec3::Y).try_normalize() else {
+            return 0.0;
+        };
+
+        (acceleration.dot(right) * self.bank).clamp(-self.max_bank, self.max_bank)
+    }
+}
+
 /// A component to randomly jitter movement to make it a little more natural
 /// Optionally add to a boid entity to apply jitter to movement.
 #[derive(Component)]
 pub struct BoidJitter(pub f32);
 
+/// The source of all randomness in the boid simulation, such as [BoidJitter].
+///
+/// By default this is seeded from entropy. Insert it with [BoidRng::from_seed] to make runs
+/// repeatable: the same seed, the same boids spawned in the same order and the same timestep
+/// give the same trajectories, whatever else is spawned alongside them.
+#[derive(Resource)]
+pub struct BoidRng {
+    rng: StdRng,
+    /// The stream the next boid to join the simulation gets
+    next_stream: u64,
+    /// How many ticks have run since seeding, so reduced [BoidLod] updates land on the same ticks
+    tick: u32,
+}
+
+impl BoidRng {
+    pub fn from_seed(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            next_stream: 0,
+            tick: 0,
+        }
+    }
+
+    /// Creates the random stream for a single boid in the current tick. Each boid gets its own
+    /// stream so the result doesn't depend on the order boids are updated in.
+    fn boid_stream(tick_seed: u64, stream: u64) -> StdRng {
+        StdRng::seed_from_u64(tick_seed ^ stream.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+    }
+}
+
+impl Default for BoidRng {
+    fn default() -> Self {
+        Self {
+            rng: StdRng::from_entropy(),
+            next_stream: 0,
+            tick: 0,
+        }
+    }
+}
+
 /// Used to generate a strong (temporary) repulsive force for boid navigation.
 /// Add it to any entity with a transform to act as a repulsor. The repulsor
 /// cannot also have a Boid component
-#[derive(Component)]
+///
+/// ```rust
+/// // scatter fish ahead of a ship, but leave the birds alone
+/// BoidRepulsor::new(0.8, 90.0)
+///     .with_groups(FISH_GROUPS)
+///     .with_falloff(BoidFalloff::Linear)
+///     .with_cone(Vec3::Y, 45f32.to_radians());
+/// ```
+#[derive(Component, Clone, Copy)]
 pub struct BoidRepulsor {
+    /// How strongly boids are pushed away. A negative strength pulls them in instead.
     pub strength: f32,
     pub range: f32,
+    /// Only boids in a collision group that shares a bit with this are affected. Boids without
+    /// a [BoidCollisionGroup] are in every group.
+    pub groups: BoidCollisionGroup,
+    pub falloff: BoidFalloff,
+    /// Only affect boids in front of the repulsor, rather than all the way around it
+    pub cone: Option<BoidRepulsorCone>,
+}
+
+impl BoidRepulsor {
+    pub fn new(strength: f32, range: f32) -> Self {
+        Self {
+            strength,
+            range,
+            groups: BoidCollisionGroup::ALL,
+            falloff: BoidFalloff::Constant,
+            cone: None,
+        }
+    }
+
+    pub fn with_groups(mut self, groups: BoidCollisionGroup) -> Self {
+        self.groups = groups;
+        self
+    }
+
+    pub fn with_falloff(mut self, falloff: BoidFalloff) -> Self {
+        self.falloff = falloff;
+        self
+    }
+
+    /// Only affect boids within `half_angle` radians of `direction`, which is in the repulsor's
+    /// local space and turns with its transform
+    pub fn with_cone(mut self, direction: Vec3, half_angle: f32) -> Self {
+        self.cone = Some(BoidRepulsorCone {
+            direction,
+            half_angle,
+        });
+        self
+    }
+
+    fn calc_repulsion(
+        &self,
+        translation: Vec3,
+        rotation: Quat,
+        position: Vec3,
+        group: BoidCollisionGroup,
+    ) -> Vec3 {
+        if !self.groups.intersects(group) {
+            return Vec3::ZERO;
+        }
+
+        let delta_pos = position - translation;
+        let distance = delta_pos.length();
+        if distance > self.range {
+            return Vec3::ZERO;
+        }
+
+        if let Some(cone) = self.cone {
+            let forward = rotation * cone.direction;
+            if forward.angle_between(delta_pos) > cone.half_angle {
+                return Vec3::ZERO;
+            }
+        }
+
+        delta_pos.normalize_or_zero() * self.strength * self.falloff.scale(distance, self.range)
+    }
+}
+
+/// The area in front of a [BoidRepulsor] that it affects
+#[derive(Clone, Copy, Debug)]
+pub struct BoidRepulsorCone {
+    /// Which way the cone points, in the repulsor's local space
+    pub direction: Vec3,
+    /// How far from `direction` the edge of the cone is, in radians
+    pub half_angle: f32,
+}
+
+/// How the strength of a force changes between its source and the edge of its range
+#[derive(Clone, Copy, Default)]
+pub enum BoidFalloff {
+    /// Full strength everywhere in range
+    #[default]
+    Constant,
+    /// Full strength at the source, fading to nothing at the edge of the range
+    Linear,
+    /// Falls off with the inverse square of the distance. Full strength is reached at a tenth of
+    /// the range, and it is capped there so boids right on top of the source don't get flung.
+    InverseSquare,
+}
+
+impl BoidFalloff {
+    /// Gets the fraction of the full strength that applies at `distance` from the source
+    pub fn scale(&self, distance: f32, range: f32) -> f32 {
+        if distance > range {
+            return 0.0;
+        }
+
+        match self {
+            BoidFalloff::Constant => 1.0,
+            BoidFalloff::Linear => 1.0 - distance / range.max(f32::EPSILON),
+            BoidFalloff::InverseSquare => {
+                let full_strength_distance = range * 0.1;
+                (full_strength_distance / distance.max(f32::EPSILON))
+                    .powi(2)
+                    .min(1.0)
+            }
+        }
+    }
+}
+
+/// Pulls boids towards it, the opposite of a [BoidRepulsor].
+/// Add it to any entity with a transform to act as an attractor. The attractor
+/// cannot also have a Boid component
+#[derive(Component, Clone, Copy)]
+pub struct BoidAttractor {
+    pub strength: f32,
+    pub range: f32,
+    pub falloff: BoidFalloff,
+}
+
+impl BoidAttractor {
+    pub fn new(strength: f32, range: f32, falloff: BoidFalloff) -> Self {
+        Self {
+            strength,
+            range,
+            falloff,
+        }
+    }
+
+    fn calc_attraction(&self, attractor_position: Vec3, position: Vec3) -> Vec3 {
+        let delta_pos = attractor_position - position;
+        let scale = self.falloff.scale(delta_pos.length(), self.range);
+
+        delta_pos.normalize_or_zero() * self.strength * scale
+    }
+}
+
+/// Steers boids along a path of waypoints.
+///
+/// Add it to a boid to steer just that boid, or to a separate entity with a [BoidCollisionGroup]
+/// to steer every boid in that group. A goal on a group moves on to the next waypoint when the
+/// centre of the group arrives.
+///
+/// Once the last waypoint is reached the goal stops steering, unless it is looping.
+#[derive(Component, Clone)]
+pub struct BoidGoal {
+    /// The points to visit, in order
+    pub waypoints: Vec<Vec3>,
+    /// How strongly boids steer towards the current waypoint
+    pub strength: f32,
+    /// How close boids have to get to a waypoint before moving on to the next one
+    pub arrival_radius: f32,
+    /// Whether to go back to the first waypoint after reaching the last one
+    pub looping: bool,
+    current: usize,
+}
+
+impl BoidGoal {
+    pub fn new(waypoints: Vec<Vec3>, strength: f32, arrival_radius: f32) -> Self {
+        Self {
+            waypoints,
+            strength,
+            arrival_radius,
+            looping: false,
+            current: 0,
+        }
+    }
+
+    pub fn with_looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    /// The waypoint boids are currently heading for, if the path isn't finished
+    pub fn target(&self) -> Option<Vec3> {
+        self.waypoints.get(self.current).copied()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.target().is_none()
+    }
+
+    /// Moves on to the next waypoint if `position` has arrived at the current one
+    fn advance(&mut self, position: Vec3) {
+        let Some(target) = self.target() else {
+            return;
+        };
+
+        if position.distance_squared(target) <= self.arrival_radius * self.arrival_radius {
+            self.current += 1;
+
+            if self.looping && self.current >= self.waypoints.len() {
+                self.current = 0;
+            }
+        }
+    }
+
+    fn calc_steering(&self, position: Vec3) -> Vec3 {
+        self.target()
+            .map(|target| (target - position).normalize_or_zero() * self.strength)
+            .unwrap_or(Vec3::ZERO)
+    }
+}
+
+/// Marks an entity as a predator that boids with [BoidPrey] react to.
+/// Add it to any entity with a transform. The predator cannot also have a Boid component.
+///
+/// The predator's velocity is worked out from how its transform moves, so it can be moved
+/// by anything.
+#[derive(Component, Clone, Copy)]
+pub struct BoidPredator {
+    /// How fast the predator has to move towards prey before the prey scatters
+    pub charge_speed: f32,
+    last_position: Option<Vec3>,
+    velocity: Vec3,
+}
+
+impl BoidPredator {
+    pub fn new(charge_speed: f32) -> Self {
+        Self {
+            charge_speed,
+            last_position: None,
+            velocity: Vec3::ZERO,
+        }
+    }
+
+    /// The predator's (smoothed) velocity
+    pub fn velocity(&self) -> Vec3 {
+        self.velocity
+    }
+
+    fn track(&mut self, position: Vec3, delta_seconds: f32) {
+        if let Some(last_position) = self.last_position {
+            if delta_seconds > 0.0 {
+                // smooth it out, as the predator might not move every frame
+                let measured = (position - last_position) / delta_seconds;
+                self.velocity = self.velocity.lerp(measured, 0.2);
+            }
+        }
+
+        self.last_position = Some(position);
+    }
+
+    fn is_charging_at(&self, predator_position: Vec3, position: Vec3) -> bool {
+        self.velocity.length_squared() > self.charge_speed * self.charge_speed
+            && self.velocity.dot(position - predator_position) > 0.0
+    }
+}
+
+/// Makes a boid react to [BoidPredator]s like prey.
+///
+/// When a predator is within `alert_range` the boid flees from it, is allowed to go faster than
+/// [BoidSpeed] `max`, and huddles closer to its flockmates in a bait ball. If the predator charges
+/// at the boid, it scatters sideways out of the predator's path.
+#[derive(Component, Clone, Copy)]
+pub struct BoidPrey {
+    /// How close a predator has to be before the boid notices it
+    pub alert_range: f32,
+    /// How strongly the boid steers away from predators. This is strongest when the predator is
+    /// closest.
+    pub flee_strength: f32,
+    /// How much [BoidSpeed] `max` is multiplied by while fleeing
+    pub flee_speed_boost: f32,
+    /// How much the `coherence` [turning strength](BoidTurningStrength) is multiplied by while a
+    /// predator is near
+    pub bait_ball_cohesion: f32,
+    /// How strongly the boid steers out of the way of a charging predator
+    pub scatter_strength: f32,
+}
+
+impl BoidPrey {
+    /// Gets the steering force away from any predators in range, or `None` if there aren't any
+    fn calc_response(&self, position: Vec3, predators: &[(Vec3, BoidPredator)]) -> Option<Vec3> {
+        let mut response = None;
+
+        for (predator_position, predator) in predators {
+            let delta_pos = position - *predator_position;
+            let distance = delta_pos.length();
+            if distance > self.alert_range {
+                continue;
+            }
+
+            let mut force = delta_pos.normalize_or_zero()
+                * self.flee_strength
+                * BoidFalloff::Linear.scale(distance, self.alert_range);
+
+            if predator.is_charging_at(*predator_position, position) {
+                // get out of the predator's path, rather than just running ahead of it
+                let charge_direction = predator.velocity.normalize_or_zero();
+                let sideways = delta_pos - charge_direction * delta_pos.dot(charge_direction);
+                force += sideways.normalize_or_zero() * self.scatter_strength;
+            }
+
+            *response.get_or_insert(Vec3::ZERO) += force;
+        }
+
+        response
+    }
 }
 
 /// The actual boid component. Attach this to any entity that should act like a boid.
 ///
-/// **NOTE**: This will take control of the entity's [Transform](bevy::prelude::Transform)
+/// **NOTE**: This will take control of the entity's [Transform](bevy::prelude::Transform),
+/// unless the entity also has a [BoidSteering] component.
+/// The boid is simulated in [FixedUpdate] and its transform is interpolated between ticks.
+/// Setting the transform yourself teleports the boid.
 ///
 /// ## Example
 /// ```rust
@@ -152,10 +933,19 @@ pub struct Boid {
     pub turning_strength: BoidTurningStrength,
     /// The boid's view configuration.
     /// This includes FOV, View range and protected range.
-    ///
-    /// **NOTE**: FOV isn't currently implemented.
     pub view_config: BoidViewConfig,
+    /// How quickly the boid can turn and speed up, see [BoidAgility]
+    pub agility: BoidAgility,
     velocity: Vec3,
+    position: Vec3,
+    previous_position: Vec3,
+    /// The translation last written to the transform, used to notice teleports
+    rendered_position: Option<Vec3>,
+    /// How far the boid is rolled into its current turn, in 3d
+    bank: f32,
+    /// Which [BoidRng] stream the boid draws from, handed out in the order boids join the
+    /// simulation so it doesn't depend on entity ids
+    stream: Option<u64>,
 }
 
 impl Boid {
@@ -168,14 +958,31 @@ impl Boid {
             speed,
             turning_strength,
             view_config,
+            agility: BoidAgility::default(),
             velocity: Vec3::ZERO,
+            position: Vec3::ZERO,
+            previous_position: Vec3::ZERO,
+            rendered_position: None,
+            bank: 0.0,
+            stream: None,
         }
     }
 
+    pub fn with_agility(mut self, agility: BoidAgility) -> Self {
+        self.agility = agility;
+        self
+    }
+
     pub fn velocity(&self) -> Vec3 {
         self.velocity
     }
 
+    /// The boid's position as of the latest simulation tick. This can be slightly ahead of
+    /// its transform, which is interpolated between ticks.
+    pub fn position(&self) -> Vec3 {
+        self.position
+    }
+
     pub fn set_velocity(&mut self, velocity: Vec3) {
         self.velocity = velocity;
     }
@@ -207,7 +1014,7 @@ impl Boid {
 ///    BoidCollisionGroup::GROUP_2
 /// )).id();
 /// ```
-#[derive(Component, Clone, Copy)]
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct BoidCollisionGroup(u32);
 
 bitflags::bitflags! {
@@ -291,6 +1098,19 @@ impl Default for BoidCollisionGroup {
     }
 }
 
+/// Add this to a boid to leave its [Transform](bevy::prelude::Transform) alone. Instead of
+/// moving and turning the entity, the boid writes the velocity it wants to move at here every
+/// simulation tick, and reads its position back from the transform.
+///
+/// This lets you move the entity yourself, e.g. to blend the boid's steering with scripted
+/// movement. [BoidBorderWrap] has no effect on these boids, as they're never teleported.
+#[derive(Component, Clone, Copy, Default)]
+pub struct BoidSteering {
+    /// The velocity the boid wants to move at, in units per second, including any drift from
+    /// a [FlowField]
+    pub velocity: Vec3,
+}
+
 /// Represents the border that a boid should stay within
 ///
 /// Each field is optional, to allow for any border you want!
@@ -309,51 +1129,63 @@ pub struct BoidBorder {
     pub back: Option<(f32, f32)>,
 }
 
+// returns `point` normalized to [0, 1] range, allowing overflow for increased strength.
+fn normalize(point: f32, start: f32, end: f32) -> f32 {
+    (point - start) / (end - start).clamp(0.0, f32::MAX)
+}
+
 impl BoidBorder {
-    fn calc_avoidance(&self, position: Vec3, boid: &Boid) -> Vec3 {
-        // returns `point` normalized to [0, 1] range, allowing overflow for increased strength.
-        fn normalize(point: f32, start: f32, end: f32) -> f32 {
-            (point - start) / (end - start).clamp(0.0, f32::MAX)
-        }
+    /// Gets the force turning the boid away from the border. Sides that are wrapped by
+    /// [BoidBorderWrap] don't push the boid back.
+    fn calc_avoidance(&self, position: Vec3, boid: &Boid, wrap: bool) -> Vec3 {
+        let (wrap_x, wrap_y, wrap_z) = if wrap {
+            (
+                self.left.is_some() && self.right.is_some(),
+                self.top.is_some() && self.bottom.is_some(),
+                self.front.is_some() && self.back.is_some(),
+            )
+        } else {
+            (false, false, false)
+        };
 
         let mut movement_vector = Vec3::ZERO;
 
-        if let Some((left, margin)) = self.left {
+        if let Some((left, margin)) = self.left.filter(|_| !wrap_x) {
             if position.x < left + margin {
                 let normalized_point = 1.0 - normalize(position.x, left, left + margin);
 
                 movement_vector.x += boid.turning_strength.border * normalized_point;
             }
         }
-        if let Some((right, margin)) = self.right {
+        if let Some((right, margin)) = self.right.filter(|_| !wrap_x) {
             if position.x > right - margin {
                 let normalized_point = normalize(position.x, right - margin, right);
 
                 movement_vector.x -= boid.turning_strength.border * normalized_point;
             }
         }
-        if let Some((top, margin)) = self.top {
+        if let Some((top, margin)) = self.top.filter(|_| !wrap_y) {
             if position.y > top - margin {
                 let normalized_point = normalize(position.y, top - margin, top);
 
                 movement_vector.y -= boid.turning_strength.border * normalized_point;
             }
         }
-        if let Some((bottom, margin)) = self.bottom {
+        if let Some((bottom, margin)) = self.bottom.filter(|_| !wrap_y) {
             if position.y < bottom + margin {
                 let normalized_point = 1.0 - normalize(position.y, bottom, bottom + margin);
 
                 movement_vector.y += boid.turning_strength.border * normalized_point;
             }
         }
-        if let Some((front, margin)) = self.front {
+        if let Some((front, margin)) = self.front.filter(|_| !wrap_z) {
             if position.z < front + margin {
                 let normalized_point = 1.0 - normalize(position.z, front, front + margin);
 
                 movement_vector.z += boid.turning_strength.border * normalized_point;
             }
         }
-        if let Some((back, margin)) = self.back {
+        if let Some((back, margin)) = self.back.filter(|_| !wrap_z) {
             if position.z > back - margin {
                 let normalized_point = normalize(position.z, back - margin, back);
 
@@ -363,139 +1195,623 @@ impl BoidBorder {
 
         movement_vector
     }
+
+    /// Moves a boid that has left through one side of the border to the opposite side.
+    /// Only sides that have both edges set (e.g. `left` and `right`) wrap.
+    fn wrap(&self, mut position: Vec3) -> Vec3 {
+        fn wrap_axis(value: f32, min: Option<(f32, f32)>, max: Option<(f32, f32)>) -> f32 {
+            match (min, max) {
+                (Some((min, _)), Some((max, _))) if max > min => {
+                    min + (value - min).rem_euclid(max - min)
+                }
+                _ => value,
+            }
+        }
+
+        position.x = wrap_axis(position.x, self.left, self.right);
+        position.y = wrap_axis(position.y, self.bottom, self.top);
+        position.z = wrap_axis(position.z, self.front, self.back);
+        position
+    }
+}
+
+/// Add this alongside a [BoidBorder] to make boids wrap around to the opposite side when
+/// they leave, instead of turning back. The world effectively becomes a torus.
+///
+/// Only sides that have both edges set wrap, so e.g. setting `left`, `right` and `top` wraps
+/// left to right while the top stays a normal border.
+///
+/// **NOTE**: boids don't see each other across the wrapped edge.
+#[derive(Component, Default)]
+pub struct BoidBorderWrap;
+
+/// A shaped region that a boid should stay within, using the same margin and
+/// [BoidTurningStrength] `border` strength as a [BoidBorder].
+///
+/// Use it to keep a flock circling something, or inside an irregular area.
+#[derive(Component, Clone)]
+pub enum BoidContainment {
+    /// A circle in 2d, or a sphere in 3d
+    Circle {
+        centre: Vec3,
+        radius: f32,
+        margin: f32,
+    },
+    /// A polygon on the xy plane. The points can go either way round, but the polygon
+    /// shouldn't cross itself.
+    Polygon { points: Vec<Vec2>, margin: f32 },
+}
+
+impl BoidContainment {
+    fn calc_avoidance(&self, position: Vec3, boid: &Boid) -> Vec3 {
+        match self {
+            BoidContainment::Circle {
+                centre,
+                radius,
+                margin,
+            } => {
+                let offset = position - *centre;
+                let distance = offset.length();
+
+                if distance > radius - margin {
+                    let normalized_point = normalize(distance, radius - margin, *radius);
+                    -offset.normalize_or_zero() * boid.turning_strength.border * normalized_point
+                } else {
+                    Vec3::ZERO
+                }
+            }
+            BoidContainment::Polygon { points, margin } => {
+                let point = position.truncate();
+                let Some(closest) = closest_point_on_polygon(points, point) else {
+                    return Vec3::ZERO;
+                };
+
+                let distance = point.distance(closest);
+                let inside = polygon_contains(points, point);
+
+                // distance into the margin, measured from the inner edge of the margin
+                let depth = if inside {
+                    margin - distance
+                } else {
+                    margin + distance
+                };
+
+                if depth <= 0.0 {
+                    return Vec3::ZERO;
+                }
+
+                let inwards = if inside {
+                    point - closest
+                } else {
+                    closest - point
+                };
+                let normalized_point = normalize(depth, 0.0, *margin);
+
+                (inwards.normalize_or_zero() * boid.turning_strength.border * normalized_point)
+                    .extend(0.0)
+            }
+        }
+    }
+}
+
+fn closest_point_on_polygon(points: &[Vec2], point: Vec2) -> Option<Vec2> {
+    (0..points.len())
+        .map(|idx| {
+            let start = points[idx];
+            let end = points[(idx + 1) % points.len()];
+            let edge = end - start;
+            let t = ((point - start).dot(edge) / edge.length_squared().max(f32::EPSILON))
+                .clamp(0.0, 1.0);
+            start + edge * t
+        })
+        .min_by(|a, b| {
+            a.distance_squared(point)
+                .total_cmp(&b.distance_squared(point))
+        })
+}
+
+fn polygon_contains(points: &[Vec2], point: Vec2) -> bool {
+    let mut inside = false;
+
+    for idx in 0..points.len() {
+        let start = points[idx];
+        let end = points[(idx + 1) % points.len()];
+
+        if (start.y > point.y) != (end.y > point.y)
+            && point.x < start.x + (point.y - start.y) / (end.y - start.y) * (end.x - start.x)
+        {
+            inside = !inside;
+        }
+    }
+
+    inside
+}
+
+/// Works out the coherence, separation and alignment forces on a boid from the neighbours it
+/// can see
+fn flocking_forces<'a>(
+    boid: &Boid,
+    position: Vec3,
+    coherence: f32,
+    is_leader: bool,
+    school: Option<BoidSchool>,
+    neighbours: impl Iterator<Item = &'a IndexedBoid>,
+) -> BoidForces {
+    let mut forces = BoidForces::default();
+    let mut separation_vector = Vec3::ZERO;
+    let mut average_velocity = Vec3::ZERO;
+    let mut average_position = Vec3::ZERO;
+    let mut neighbour_weight = 0.0;
+
+    for neighbour in neighbours {
+        let distance = position.distance(neighbour.position);
+
+        if distance < boid.view_config.protected_range {
+            let normalized_diff = (neighbour.position - position).normalize();
+            let strength = boid.view_config.protected_range - distance;
+
+            separation_vector -= normalized_diff * strength;
+        }
+
+        // leaders go their own way, and followers favour their own school's leader over their
+        // peers
+        let weight = match neighbour.leader {
+            _ if is_leader => 0.0,
+            Some(leader) if neighbour.school == school => leader.weight,
+            _ => 1.0,
+        };
+
+        average_velocity += neighbour.velocity * weight;
+        average_position += neighbour.position * weight;
+        neighbour_weight += weight;
+    }
+
+    if neighbour_weight > 0.0 {
+        average_velocity /= neighbour_weight;
+        average_position /= neighbour_weight;
+
+        forces.alignment = (average_velocity - boid.velocity) * boid.turning_strength.alignment;
+        forces.coherence = (average_position - position) * coherence;
+    }
+
+    forces.separation = separation_vector * boid.turning_strength.separation;
+    forces
 }
 
 mod systems {
     use bevy::{
-        prelude::{Color, Entity, Gizmos, Query, Res, Transform, Vec2, Without},
-        time::Time,
+        prelude::{
+            Color, Entity, Gizmos, Has, Mut, Quat, Query, Res, ResMut, Transform, Vec2, With,
+            Without,
+        },
+        time::{Fixed, Time},
     };
     use rand::Rng;
 
     use super::*;
 
+    /// Gets the space and debug settings for a boid, from its [Flock] if it has one
+    fn boid_settings(
+        config: &BoidsConfig,
+        flocks: &Query<&Flock>,
+        flock: Option<&BoidFlock>,
+    ) -> (BoidSpace, bool) {
+        match flock.and_then(|flock| flocks.get(flock.0).ok()) {
+            Some(flock) => (flock.space, flock.debug),
+            None => (config.space, config.debug),
+        }
+    }
+
+    /// Gets the level of detail settings for a boid, from its [Flock] if it has one
+    fn boid_lod(
+        config: &BoidsConfig,
+        flocks: &Query<&Flock>,
+        flock: Option<&BoidFlock>,
+    ) -> Option<BoidLod> {
+        match flock.and_then(|flock| flocks.get(flock.0).ok()) {
+            Some(flock) => flock.lod,
+            None => config.lod,
+        }
+    }
+
+    /// Picks up boids that were just spawned or had their transform moved outside of the simulation
+    pub(super) fn sync_boid_positions(
+        mut rng: ResMut<BoidRng>,
+        mut boid_query: Query<(&Transform, &mut Boid, Has<BoidSteering>)>,
+    ) {
+        for (transform, mut boid, steering) in boid_query.iter_mut() {
+            if boid.stream.is_none() {
+                boid.stream = Some(rng.next_stream);
+                rng.next_stream += 1;
+            }
+
+            // steering boids are always moved by someone else
+            if steering || boid.rendered_position != Some(transform.translation) {
+                boid.position = transform.translation;
+                boid.previous_position = transform.translation;
+                boid.rendered_position = Some(transform.translation);
+            }
+        }
+    }
+
+    pub(super) fn rebuild_spatial_index(
+        config: Res<BoidsConfig>,
+        flocks: Query<&Flock>,
+        focuses: Query<&Transform, With<BoidLodFocus>>,
+        mut rng: ResMut<BoidRng>,
+        mut index: ResMut<BoidSpatialIndex>,
+        boid_query: Query<(
+            &Boid,
+            Option<&BoidCollisionGroup>,
+            Option<&BoidFlock>,
+            Option<&BoidSchool>,
+            Option<&BoidLeader>,
+            Entity,
+        )>,
+    ) {
+        let cell_size = boid_query
+            .iter()
+            .map(|(boid, _, _, _, _, _)| boid.view_config.view_range)
+            .fold(0.0, f32::max);
+
+        let focuses = focuses
+            .iter()
+            .map(|transform| transform.translation)
+            .collect::<Vec<_>>();
+        rng.tick = rng.tick.wrapping_add(1);
+        let tick = rng.tick;
+
+        index.rebuild(
+            boid_query
+                .iter()
+                .map(
+                    |(boid, collision_group, flock, school, leader, entity)| IndexedBoid {
+                        entity,
+                        position: boid.position,
+                        velocity: boid.velocity,
+                        collision_group: collision_group.copied(),
+                        flock: flock.map(|flock| flock.0),
+                        school: school.copied(),
+                        view_config: boid.view_config,
+                        space: boid_settings(&config, &flocks, flock).0,
+                        leader: leader.copied(),
+                        steer_ticks: boid_lod(&config, &flocks, flock).map_or(1, |lod| {
+                            let stagger = boid.stream.unwrap_or_default() as u32;
+                            lod.steer_ticks(boid.position, &focuses, tick, stagger)
+                        }),
+                    },
+                ),
+            cell_size,
+        );
+        index.find_neighbours();
+    }
+
+    pub(super) fn track_predators(
+        time: Res<Time>,
+        mut predators: Query<(&Transform, &mut BoidPredator), Without<Boid>>,
+    ) {
+        for (transform, mut predator) in predators.iter_mut() {
+            predator.track(transform.translation, time.delta_seconds());
+        }
+    }
+
+    /// Moves group goals on to their next waypoint once the centre of their group arrives
+    pub(super) fn advance_group_goals(
+        index: Res<BoidSpatialIndex>,
+        mut goals: Query<(&mut BoidGoal, &BoidCollisionGroup), Without<Boid>>,
+    ) {
+        for (mut goal, goal_group) in goals.iter_mut() {
+            let (sum, count) = index
+                .boids()
+                .iter()
+                .filter(|boid| {
+                    boid.collision_group
+                        .unwrap_or_default()
+                        .intersects(*goal_group)
+                })
+                .fold((Vec3::ZERO, 0), |(sum, count), boid| {
+                    (sum + boid.position, count + 1)
+                });
+
+            if count > 0 {
+                goal.advance(sum / count as f32);
+            }
+        }
+    }
+
     pub(super) fn handle_boid_movement(
         time: Res<Time>,
+        config: Res<BoidsConfig>,
+        mut rng: ResMut<BoidRng>,
+        index: Res<BoidSpatialIndex>,
+        behaviour_forces: Res<BoidBehaviourForces>,
+        flow: Option<Res<FlowField>>,
+        flocks: Query<&Flock>,
         repulsors: Query<(&Transform, &BoidRepulsor), Without<Boid>>,
+        obstacles: Query<(&Transform, &BoidObstacle), Without<Boid>>,
+        attractors: Query<(&Transform, &BoidAttractor), Without<Boid>>,
+        group_goals: Query<(&BoidGoal, &BoidCollisionGroup), Without<Boid>>,
+        predators: Query<(&Transform, &BoidPredator), Without<Boid>>,
         mut boid_query: Query<(
-            &mut Transform,
             &mut Boid,
             Option<&BoidBorder>,
+            Option<&BoidBorderWrap>,
+            Option<&BoidContainment>,
             Option<&BoidCollisionGroup>,
             Option<&BoidJitter>,
+            Option<&BoidFlock>,
+            Option<&mut BoidGoal>,
+            Option<&BoidPrey>,
+            Option<&mut BoidDebug>,
+            Option<&mut BoidSteering>,
             Entity,
         )>,
     ) {
-        let boids = boid_query
+        let repulsors = repulsors
             .iter()
-            .map(|(transform, boid, _, collision_group, _, entity)| {
-                (*transform, *boid, collision_group.copied(), entity)
-            })
+            .map(|(transform, repulsor)| (transform.translation, transform.rotation, *repulsor))
             .collect::<Vec<_>>();
+        let obstacles = obstacles
+            .iter()
+            .map(|(transform, obstacle)| (transform.translation, transform.rotation, *obstacle))
+            .collect::<Vec<_>>();
+        let attractors = attractors
+            .iter()
+            .map(|(transform, attractor)| (transform.translation, *attractor))
+            .collect::<Vec<_>>();
+        let group_goals = group_goals.iter().collect::<Vec<_>>();
+        let predators = predators
+            .iter()
+            .map(|(transform, predator)| (transform.translation, *predator))
+            .collect::<Vec<_>>();
+        let boids = index.boids();
+        let flow = flow.as_deref();
+        let delta_seconds = time.delta_seconds();
+        let tick_seed = rng.rng.gen::<u64>();
 
-        for (mut transform, mut boid, border, collision_group, jitter, entity) in
-            boid_query.iter_mut()
-        {
-            let mut movement_vector = Vec3::ZERO;
-
-            let mut separation_vector = Vec3::ZERO;
-            let mut average_velocity = Vec3::ZERO;
-            let mut average_position = Vec3::ZERO;
-            let mut neighbouring_boids = 0;
-
-            for (
-                neighbour_transform,
-                neighbour_boid,
-                neighbour_collision_group,
-                neighbour_entity,
-            ) in &boids
-            {
-                if *neighbour_entity == entity {
-                    continue;
+        boid_query.par_iter_mut().for_each(
+            |(
+                mut boid,
+                border,
+                border_wrap,
+                containment,
+                collision_group,
+                jitter,
+                flock,
+                goal,
+                prey,
+                mut debug,
+                steering,
+                entity,
+            )| {
+                let position = boid.position;
+                let mut flee_force = Vec3::ZERO;
+                let mut coherence = boid.turning_strength.coherence;
+                let mut max_speed = boid.speed.max;
+
+                if let Some(debug) = debug.as_mut() {
+                    debug.last_neighbours.clear();
                 }
 
-                if let Some(collision_group) = collision_group {
-                    if let Some(neighbour_collision_group) = neighbour_collision_group {
-                        if !collision_group.intersects(*neighbour_collision_group) {
-                            continue;
-                        }
+                let (space, _) = boid_settings(&config, &flocks, flock);
+                let indexed = index.index_of(entity);
+
+                // the current carries the boid along without changing where it's heading
+                let drift = flow.map_or(Vec3::ZERO, |flow| space.project(flow.sample(position)));
+
+                // boids spawned since the index was rebuilt get a full update
+                let steer_ticks = indexed.map_or(1, |idx| boids[idx].steer_ticks);
+                if steer_ticks == 0 {
+                    // too far away to be worth steering this tick, so carry on in a straight line
+                    move_boid(
+                        &mut boid,
+                        steering,
+                        border,
+                        border_wrap,
+                        drift,
+                        delta_seconds,
+                    );
+                    return;
+                }
+
+                // catch up on the steering missed since the last update
+                let steer_seconds = delta_seconds * steer_ticks as f32;
+
+                // react to predators
+                if let Some(prey) = prey {
+                    if let Some(flee) = prey.calc_response(position, &predators) {
+                        flee_force = space.project(flee);
+                        coherence *= prey.bait_ball_cohesion;
+                        max_speed *= prey.flee_speed_boost;
                     }
                 }
 
-                let distance = transform
-                    .translation
-                    .distance(neighbour_transform.translation);
+                let neighbours = indexed.map_or(&[][..], |idx| index.neighbours(idx));
+                let is_leader = indexed.is_some_and(|idx| boids[idx].leader.is_some());
+                let school = indexed.and_then(|idx| boids[idx].school);
+                let neighbours = neighbours.iter().map(|idx| &boids[*idx]);
 
-                if distance <= boid.view_config.view_range {
-                    if distance < boid.view_config.protected_range {
-                        let normalized_diff =
-                            (neighbour_transform.translation - transform.translation).normalize();
-                        let strength = boid.view_config.protected_range - distance;
+                if let Some(debug) = debug.as_mut().filter(|debug| debug.neighbours) {
+                    let positions = neighbours.clone().map(|neighbour| neighbour.position);
+                    debug.last_neighbours.extend(positions);
+                }
 
-                        separation_vector -= normalized_diff * strength;
-                    }
+                let mut forces =
+                    flocking_forces(&boid, position, coherence, is_leader, school, neighbours);
+                forces.flee = flee_force;
 
-                    average_velocity += neighbour_boid.velocity;
-                    average_position += neighbour_transform.translation;
-                    neighbouring_boids += 1;
+                if let Some(border) = border {
+                    forces.border += border.calc_avoidance(position, &boid, border_wrap.is_some());
                 }
-            }
 
-            if neighbouring_boids > 0 {
-                average_velocity /= neighbouring_boids as f32;
-                average_position /= neighbouring_boids as f32;
+                if let Some(containment) = containment {
+                    forces.border += containment.calc_avoidance(position, &boid);
+                }
 
-                movement_vector +=
-                    (average_velocity - boid.velocity) * boid.turning_strength.alignment;
-                movement_vector +=
-                    (average_position - transform.translation) * boid.turning_strength.coherence;
-            }
+                // apply jitter
+                if let Some(jitter) = jitter {
+                    let stream = boid.stream.unwrap_or_default();
+                    let mut rng = BoidRng::boid_stream(tick_seed, stream);
+                    forces.jitter = (rng.gen_range(-jitter.0..jitter.0) * Vec2::ONE).extend(0.);
+                }
 
-            movement_vector += separation_vector * boid.turning_strength.separation;
+                // apply repulsors
+                let group = collision_group.copied().unwrap_or_default();
+                for (translation, rotation, repulsor) in &repulsors {
+                    forces.repulsor += space.project(repulsor.calc_repulsion(
+                        *translation,
+                        *rotation,
+                        position,
+                        group,
+                    ));
+                }
 
-            if let Some(border) = border {
-                movement_vector += border.calc_avoidance(transform.translation, &boid);
-            }
+                // look ahead for obstacles
+                for (translation, rotation, obstacle) in &obstacles {
+                    forces.obstacle += space.project(obstacle.calc_avoidance(
+                        *translation,
+                        *rotation,
+                        position,
+                        boid.velocity,
+                    ));
+                }
 
-            // apply jitter
-            if let Some(jitter) = jitter {
-                movement_vector +=
-                    (rand::thread_rng().gen_range(-jitter.0..jitter.0) * Vec2::ONE).extend(0.);
-            }
+                // apply custom behaviours
+                if let Some(force) = indexed.and_then(|idx| behaviour_forces.0.get(idx)) {
+                    forces.custom = *force;
+                }
+
+                // apply attractors
+                for (attractor_position, attractor) in &attractors {
+                    forces.attractor +=
+                        space.project(attractor.calc_attraction(*attractor_position, position));
+                }
 
-            // apply repulsors
-            for (repulsor_tx, repulsor) in &repulsors {
-                let delta_pos = transform.translation - repulsor_tx.translation;
-                if delta_pos.length_squared() > repulsor.range * repulsor.range {
-                    continue;
+                // apply goals, both for this boid and for any group it is in
+                if let Some(mut goal) = goal {
+                    goal.advance(position);
+                    forces.goal += space.project(goal.calc_steering(position));
                 }
 
-                movement_vector += delta_pos.normalize_or_zero() * repulsor.strength;
+                for (goal, goal_group) in &group_goals {
+                    if group.intersects(**goal_group) {
+                        forces.goal += space.project(goal.calc_steering(position));
+                    }
+                }
+
+                let movement_vector = forces.alignment
+                    + forces.coherence
+                    + forces.separation
+                    + forces.border
+                    + forces.jitter
+                    + forces.repulsor
+                    + forces.obstacle
+                    + forces.custom
+                    + forces.flee
+                    + forces.attractor
+                    + forces.goal;
+
+                if let Some(debug) = debug.as_mut() {
+                    debug.last_forces = forces;
+                }
+
+                let new_velocity = boid.agility.limit(
+                    boid.velocity,
+                    boid.velocity + movement_vector * steer_seconds * STEERING_REFERENCE_RATE,
+                    steer_seconds,
+                    space,
+                );
+
+                let new_velocity = if new_velocity.length_squared() > 0.0 {
+                    new_velocity.clamp_length(boid.speed.min, max_speed)
+                } else {
+                    Vec3::X
+                };
+
+                // ease into the bank, so one sharp tick doesn't flip the boid over
+                let target_bank = boid
+                    .agility
+                    .calc_bank(new_velocity, (new_velocity - boid.velocity) / steer_seconds);
+                boid.bank +=
+                    (target_bank - boid.bank) * (1.0 - (-BANK_RESPONSE * steer_seconds).exp());
+                boid.velocity = new_velocity;
+
+                move_boid(
+                    &mut boid,
+                    steering,
+                    border,
+                    border_wrap,
+                    drift,
+                    delta_seconds,
+                );
+            },
+        );
+    }
+
+    /// Moves a boid along its velocity for one tick, or hands the velocity over if the boid is
+    /// moved by someone else
+    fn move_boid(
+        boid: &mut Boid,
+        steering: Option<Mut<BoidSteering>>,
+        border: Option<&BoidBorder>,
+        border_wrap: Option<&BoidBorderWrap>,
+        drift: Vec3,
+        delta_seconds: f32,
+    ) {
+        if let Some(mut steering) = steering {
+            // leave moving the boid to whoever owns its transform
+            steering.velocity = boid.velocity + drift;
+            return;
+        }
+
+        let position = boid.position;
+        let new_position = position + (boid.velocity + drift) * delta_seconds;
+
+        match border.filter(|_| border_wrap.is_some()) {
+            Some(border) if border.wrap(new_position) != new_position => {
+                // teleport, so don't interpolate across the screen
+                boid.position = border.wrap(new_position);
+                boid.previous_position = boid.position;
             }
+            _ => {
+                boid.previous_position = position;
+                boid.position = new_position;
+            }
+        }
+    }
 
-            let new_velocity = boid.velocity + movement_vector;
+    pub(super) fn interpolate_boid_transforms(
+        fixed_time: Res<Time<Fixed>>,
+        mut boid_query: Query<(&mut Transform, &mut Boid), Without<BoidSteering>>,
+    ) {
+        let overstep = fixed_time.overstep_fraction();
 
-            boid.velocity = if new_velocity.length_squared() > 0.0 {
-                new_velocity.clamp_length(boid.speed.min, boid.speed.max)
-            } else {
-                Vec3::X
-            };
+        for (mut transform, mut boid) in boid_query.iter_mut() {
+            // boids that haven't been simulated yet stay where they were spawned
+            if boid.rendered_position.is_none() {
+                continue;
+            }
 
-            transform.translation += boid.velocity * time.delta_seconds();
+            let rendered_position = boid.previous_position.lerp(boid.position, overstep);
+            transform.translation = rendered_position;
+            boid.rendered_position = Some(rendered_position);
         }
     }
 
     pub(super) fn handle_boid_orientation(
-        mut boid_query: Query<(&mut Transform, &Boid)>,
+        mut boid_query: Query<(&mut Transform, &Boid, Option<&BoidFlock>), Without<BoidSteering>>,
+        flocks: Query<&Flock>,
         config: Res<BoidsConfig>,
     ) {
-        for (mut transform, boid) in boid_query.iter_mut() {
+        for (mut transform, boid, flock) in boid_query.iter_mut() {
             let forward = transform.forward();
 
-            match config.space {
+            match boid_settings(&config, &flocks, flock).0 {
                 BoidSpace::TwoDimensional => transform.look_to(*forward, boid.velocity.normalize()),
                 BoidSpace::ThreeDimensional => {
-                    transform.look_to(boid.velocity.normalize(), *forward)
+                    // keep the boid upright, apart from leaning into turns
+                    transform.look_to(boid.velocity.normalize(), Vec3::Y);
+                    transform.rotate_local_z(-boid.bank);
                 }
             }
         }
@@ -503,51 +1819,332 @@ mod systems {
 
     pub(super) fn handle_boid_gizmos(
         config: Res<BoidsConfig>,
-        boid_query: Query<(&Transform, &Boid)>,
+        flocks: Query<&Flock>,
+        boid_query: Query<(&Transform, &Boid, Option<&BoidFlock>, Option<&BoidDebug>)>,
+        repulsors: Query<
+            (&Transform, &BoidRepulsor, &BoidDebug, Option<&BoidFlock>),
+            Without<Boid>,
+        >,
+        obstacles: Query<(&Transform, &BoidObstacle, &BoidDebug), Without<Boid>>,
         mut gizmos: Gizmos,
     ) {
-        if config.debug {
-            match config.space {
-                BoidSpace::TwoDimensional => {
-                    for (transform, boid) in boid_query.iter() {
-                        for angle in
-                            -(boid.view_config.fov as i32 / 2)..boid.view_config.fov as i32 / 2
-                        {
-                            let angle_radians = (angle as f32).to_radians();
-
-                            gizmos.ray_2d(
-                                transform.translation.truncate(),
-                                transform
-                                    .up()
-                                    .truncate()
-                                    .rotate(Vec2::from_angle(angle_radians))
-                                    * boid.view_config.view_range,
-                                Color::srgba(1.0, 0.0, 0.0, 0.1),
-                            );
-                        }
+        for (transform, boid, flock, debug) in boid_query.iter() {
+            let (space, flock_debug) = boid_settings(&config, &flocks, flock);
+            let position = transform.translation;
+
+            if flock_debug || debug.is_some_and(|debug| debug.view_range) {
+                draw_view_range(&mut gizmos, space, transform, boid);
+            }
+
+            let Some(debug) = debug else {
+                continue;
+            };
+
+            if debug.protected_range {
+                draw_range(
+                    &mut gizmos,
+                    space,
+                    position,
+                    boid.view_config.protected_range,
+                    Color::srgba(1.0, 0.5, 0.0, 0.5),
+                );
+            }
+
+            if debug.velocity {
+                gizmos.arrow(position, position + boid.velocity, Color::WHITE);
+            }
+
+            if debug.forces {
+                let forces = &debug.last_forces;
+
+                for (force, color) in [
+                    (forces.coherence, Color::srgb(0.0, 1.0, 0.0)),
+                    (forces.separation, Color::srgb(1.0, 0.0, 0.0)),
+                    (forces.alignment, Color::srgb(0.0, 0.5, 1.0)),
+                    (forces.border, Color::srgb(1.0, 1.0, 0.0)),
+                    (forces.repulsor, Color::srgb(1.0, 0.0, 1.0)),
+                    (forces.obstacle, Color::srgb(0.5, 0.0, 1.0)),
+                    (forces.jitter, Color::srgb(0.5, 0.5, 0.5)),
+                    (forces.custom, Color::srgb(1.0, 0.5, 0.0)),
+                    (forces.flee, Color::srgb(1.0, 0.0, 0.5)),
+                    (forces.attractor, Color::srgb(0.0, 1.0, 0.5)),
+                    (forces.goal, Color::srgb(0.5, 1.0, 1.0)),
+                ] {
+                    if force != Vec3::ZERO {
+                        gizmos.arrow(position, position + force * debug.force_scale, color);
                     }
                 }
-                BoidSpace::ThreeDimensional => {
-                    for (transform, boid) in boid_query.iter() {
-                        for angle in
-                            -(boid.view_config.fov as i32) / 2..boid.view_config.fov as i32 / 2
-                        {
-                            let angle_radians = (angle as f32).to_radians();
-
-                            gizmos.ray(
-                                transform.translation,
-                                transform
-                                    .up()
-                                    .truncate()
-                                    .rotate(Vec2::from_angle(angle_radians))
-                                    .extend(0.0)
-                                    * boid.view_config.view_range,
-                                Color::srgba(1.0, 0.0, 0.0, 0.1),
-                            );
-                        }
-                    }
+            }
+
+            if debug.neighbours {
+                for neighbour in &debug.last_neighbours {
+                    gizmos.line(position, *neighbour, Color::srgba(0.0, 1.0, 1.0, 0.3));
                 }
             }
         }
+
+        for (transform, repulsor, debug, flock) in repulsors.iter() {
+            if !debug.repulsor_range {
+                continue;
+            }
+
+            let (space, _) = boid_settings(&config, &flocks, flock);
+            let color = Color::srgba(1.0, 0.0, 1.0, 0.5);
+            draw_range(
+                &mut gizmos,
+                space,
+                transform.translation,
+                repulsor.range,
+                color,
+            );
+
+            // mark the edges of the cone, as seen from above
+            if let Some(cone) = repulsor.cone {
+                let forward = (transform.rotation * cone.direction).normalize_or_zero();
+
+                for angle in [-cone.half_angle, cone.half_angle] {
+                    gizmos.ray(
+                        transform.translation,
+                        Quat::from_rotation_z(angle) * forward * repulsor.range,
+                        color,
+                    );
+                }
+            }
+        }
+
+        for (transform, obstacle, debug) in obstacles.iter() {
+            if debug.obstacle_shape {
+                gizmos.linestrip(
+                    obstacle.shape.outline().into_iter().map(|point| {
+                        transform.translation + transform.rotation * point.extend(0.0)
+                    }),
+                    Color::srgba(0.5, 0.0, 1.0, 0.5),
+                );
+            }
+        }
+    }
+
+    fn draw_range(gizmos: &mut Gizmos, space: BoidSpace, position: Vec3, range: f32, color: Color) {
+        match space {
+            BoidSpace::TwoDimensional => {
+                gizmos.circle_2d(position.truncate(), range, color);
+            }
+            BoidSpace::ThreeDimensional => {
+                gizmos.sphere(position, Quat::IDENTITY, range, color);
+            }
+        }
+    }
+
+    fn draw_view_range(gizmos: &mut Gizmos, space: BoidSpace, transform: &Transform, boid: &Boid) {
+        match space {
+            BoidSpace::TwoDimensional => {
+                let heading = boid
+                    .velocity
+                    .truncate()
+                    .try_normalize()
+                    .unwrap_or_else(|| transform.up().truncate());
+                let half_fov = boid.view_config.fov.min(360) as i32 / 2;
+
+                for angle in -half_fov..=half_fov {
+                    let angle_radians = (angle as f32).to_radians();
+
+                    gizmos.ray_2d(
+                        transform.translation.truncate(),
+                        heading.rotate(Vec2::from_angle(angle_radians))
+                            * boid.view_config.view_range,
+                        Color::srgba(1.0, 0.0, 0.0, 0.1),
+                    );
+                }
+            }
+            BoidSpace::ThreeDimensional => {
+                let heading = boid
+                    .velocity
+                    .try_normalize()
+                    .unwrap_or_else(|| *transform.forward());
+                let (tangent, bitangent) = heading.any_orthonormal_pair();
+                let (sin_half_fov, cos_half_fov) = boid.view_config.half_fov().sin_cos();
+
+                // draw the edge of the view cone
+                for step in 0..36 {
+                    let (sin, cos) = (step as f32 * 10.0).to_radians().sin_cos();
+                    let direction =
+                        heading * cos_half_fov + (tangent * cos + bitangent * sin) * sin_half_fov;
+
+                    gizmos.ray(
+                        transform.translation,
+                        direction * boid.view_config.view_range,
+                        Color::srgba(1.0, 0.0, 0.0, 0.1),
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::{
+        app::App,
+        asset::AssetPlugin,
+        ecs::system::RunSystemOnce,
+        prelude::{Time, Transform},
+        MinimalPlugins,
+    };
+    use rand::Rng;
+
+    use super::*;
+    use crate::spatial::random_boids;
+
+    /// Remembers the order the test spawned a boid in, so runs can be compared boid by boid
+    #[derive(Component)]
+    struct SpawnOrder(usize);
+
+    /// Runs a flock of jittery boids for a second of fixed ticks and returns where they ended up.
+    /// `unrelated` entities are spawned first to shift the boids' entity ids.
+    fn run_jittery_flock(seed: u64, unrelated: usize, lod: Option<BoidLod>) -> Vec<Transform> {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), BoidsPlugin))
+            .insert_resource(BoidRng::from_seed(seed))
+            .insert_resource(BoidsConfig {
+                lod,
+                ..Default::default()
+            });
+
+        if lod.is_some() {
+            app.world_mut()
+                .spawn((BoidLodFocus, Transform::from_translation(Vec3::ZERO)));
+        }
+
+        for _ in 0..unrelated {
+            app.world_mut().spawn_empty();
+        }
+
+        let mut rng = StdRng::seed_from_u64(0);
+        for order in 0..50 {
+            let mut boid = Boid::new(
+                BoidSpeed::new(10.0, 40.0),
+                BoidTurningStrength::new(0.3, 0.5, 0.1, 1.0),
+                BoidViewConfig::new(270, 4.0, 20.0),
+            );
+            boid.set_velocity(
+                Vec3::new(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0), 0.0) * 20.0,
+            );
+            let position = Vec3::new(rng.gen_range(-50.0..50.0), rng.gen_range(-50.0..50.0), 0.0);
+
+            app.world_mut().spawn((
+                boid,
+                BoidJitter(5.0),
+                Transform::from_translation(position),
+                SpawnOrder(order),
+            ));
+        }
+
+        for _ in 0..64 {
+            app.world_mut()
+                .resource_mut::<Time>()
+                .advance_by(Duration::from_secs_f64(1.0 / 64.0));
+            app.world_mut().run_schedule(FixedUpdate);
+        }
+        app.world_mut()
+            .run_system_once(systems::interpolate_boid_transforms);
+
+        let mut boids = app
+            .world_mut()
+            .query::<(&SpawnOrder, &Transform)>()
+            .iter(app.world())
+            .map(|(order, transform)| (order.0, *transform))
+            .collect::<Vec<_>>();
+        boids.sort_by_key(|(order, _)| *order);
+        boids.into_iter().map(|(_, transform)| transform).collect()
+    }
+
+    #[test]
+    fn same_seed_gives_same_flock() {
+        let first = run_jittery_flock(42, 0, None);
+        let second = run_jittery_flock(42, 17, None);
+        assert_eq!(first, second);
+
+        let other_seed = run_jittery_flock(43, 0, None);
+        assert_ne!(first, other_seed);
+    }
+
+    #[test]
+    fn same_seed_gives_same_flock_with_lod() {
+        // the flock spans all three tiers, so most boids skip steering on most ticks
+        let lod = Some(BoidLod::new(15.0, 40.0, 4));
+
+        let first = run_jittery_flock(42, 0, lod);
+        let second = run_jittery_flock(42, 17, lod);
+        assert_eq!(first, second);
+
+        assert_ne!(first, run_jittery_flock(42, 0, None));
+    }
+
+    #[test]
+    fn u_turns_stay_flat_in_2d() {
+        let agility = BoidAgility::new(Some(1.0), None);
+
+        for heading in [Vec3::X, Vec3::Y, Vec3::new(-0.6, 0.8, 0.0)] {
+            let turned = agility.limit(
+                heading * 20.0,
+                -heading * 20.0,
+                0.1,
+                BoidSpace::TwoDimensional,
+            );
+
+            assert_eq!(turned.z, 0.0, "{heading} turned to {turned}");
+            assert!((turned.angle_between(heading) - 0.1).abs() < 1e-4);
+            assert!((turned.length() - 20.0).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn grid_steering_matches_brute_force() {
+        let boids = random_boids(7, 400);
+        let cell_size = boids
+            .iter()
+            .map(|boid| boid.view_config.view_range)
+            .fold(0.0, f32::max);
+
+        let mut index = BoidSpatialIndex::default();
+        index.rebuild(boids, cell_size);
+        index.find_neighbours();
+
+        let indexed = index.boids();
+        for (idx, indexed_boid) in indexed.iter().enumerate() {
+            let mut boid = Boid::new(
+                BoidSpeed::new(10.0, 40.0),
+                BoidTurningStrength::new(0.3, 0.5, 0.1, 1.0),
+                indexed_boid.view_config,
+            );
+            boid.set_velocity(indexed_boid.velocity);
+
+            let steer = |neighbours: &[usize]| {
+                flocking_forces(
+                    &boid,
+                    indexed_boid.position,
+                    boid.turning_strength.coherence,
+                    false,
+                    indexed_boid.school,
+                    neighbours.iter().map(|idx| &indexed[*idx]),
+                )
+            };
+
+            let grid = steer(index.neighbours(idx));
+            let brute_force = steer(&index.brute_force_neighbours(idx));
+
+            for (grid, brute_force) in [
+                (grid.coherence, brute_force.coherence),
+                (grid.separation, brute_force.separation),
+                (grid.alignment, brute_force.alignment),
+            ] {
+                assert!(
+                    grid.abs_diff_eq(brute_force, 1e-4),
+                    "boid {idx}: {grid} != {brute_force}"
+                );
+            }
+        }
     }
 }
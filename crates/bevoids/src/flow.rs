@@ -0,0 +1,164 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::{Resource, Vec2, Vec3};
+
+/// Moving water (or air) that carries every boid along, on top of its own swimming.
+///
+/// Insert it as a resource to turn it on. The flow is sampled at each boid's position every
+/// simulation tick and added to how far it moves, so boids drift with the current without it
+/// changing where they're heading. Use [FlowField::sample] to carry other entities along with
+/// the same current.
+///
+/// Flows are in units per second, in the xy plane, so 3d boids only drift sideways.
+///
+/// ```rust
+/// app.insert_resource(FlowField {
+///     current: Vec3::new(5.0, 0.0, 0.0),
+///     pattern: FlowPattern::CurlNoise {
+///         frequency: 0.005,
+///         strength: 4.0,
+///         offset: Vec2::ZERO,
+///         seed: 7,
+///     },
+/// });
+/// ```
+#[derive(Resource, Clone, Debug, Default)]
+pub struct FlowField {
+    /// A current that is the same everywhere
+    pub current: Vec3,
+    /// Variation layered on top of the current
+    pub pattern: FlowPattern,
+}
+
+impl FlowField {
+    /// Gets the velocity of the flow at a point
+    pub fn sample(&self, position: Vec3) -> Vec3 {
+        self.current + self.pattern.sample(position.truncate()).extend(0.0)
+    }
+}
+
+/// The shape of the flow in a [FlowField], apart from its constant current
+#[derive(Clone, Debug, Default)]
+pub enum FlowPattern {
+    /// Just the current
+    #[default]
+    None,
+    /// Hand made flow, e.g. loaded from a level
+    Grid(FlowGrid),
+    /// Swirling eddies from curl noise. The flow never converges or diverges, so boids don't
+    /// bunch up in sinks or get pushed out of sources.
+    CurlNoise {
+        /// How many eddies there are per unit, roughly. Small numbers give big, lazy swirls.
+        frequency: f32,
+        /// Roughly how fast the flow is. The fastest parts can be about twice this.
+        strength: f32,
+        /// Moves the pattern around, e.g. to scroll it with the camera or animate it over time
+        offset: Vec2,
+        seed: u32,
+    },
+}
+
+impl FlowPattern {
+    fn sample(&self, position: Vec2) -> Vec2 {
+        match self {
+            FlowPattern::None => Vec2::ZERO,
+            FlowPattern::Grid(grid) => grid.sample(position),
+            FlowPattern::CurlNoise {
+                frequency,
+                strength,
+                offset,
+                seed,
+            } => curl_noise(position * *frequency + *offset, *seed) * *strength,
+        }
+    }
+}
+
+/// A grid of flow vectors, blended bilinearly between the cells. Outside the grid the flow
+/// matches the nearest edge.
+#[derive(Clone, Debug)]
+pub struct FlowGrid {
+    /// Where the centre of the first cell is
+    pub origin: Vec2,
+    pub cell_size: f32,
+    /// The number of cells in each row
+    pub width: usize,
+    /// The flow in each cell, row by row from the origin
+    pub vectors: Vec<Vec2>,
+}
+
+impl FlowGrid {
+    pub fn new(origin: Vec2, cell_size: f32, width: usize, vectors: Vec<Vec2>) -> Self {
+        Self {
+            origin,
+            cell_size,
+            width,
+            vectors,
+        }
+    }
+
+    /// Gets the flow at a point, blended from the four closest cells
+    pub fn sample(&self, position: Vec2) -> Vec2 {
+        if self.width == 0 || self.vectors.len() < self.width {
+            return Vec2::ZERO;
+        }
+
+        let height = self.vectors.len() / self.width;
+        let max = Vec2::new((self.width - 1) as f32, (height - 1) as f32);
+        let local =
+            ((position - self.origin) / self.cell_size.max(f32::EPSILON)).clamp(Vec2::ZERO, max);
+
+        let cell = local.floor();
+        let fraction = local - cell;
+        let (x, y) = (cell.x as usize, cell.y as usize);
+        let (next_x, next_y) = ((x + 1).min(self.width - 1), (y + 1).min(height - 1));
+
+        let at = |x: usize, y: usize| self.vectors[y * self.width + x];
+
+        at(x, y).lerp(at(next_x, y), fraction.x).lerp(
+            at(x, next_y).lerp(at(next_x, next_y), fraction.x),
+            fraction.y,
+        )
+    }
+}
+
+/// The curl of 2d gradient noise, i.e. the noise is used as a stream function and the flow runs
+/// along its contour lines.
+fn curl_noise(point: Vec2, seed: u32) -> Vec2 {
+    const EPSILON: f32 = 0.01;
+
+    let d_dx = gradient_noise(point + Vec2::X * EPSILON, seed)
+        - gradient_noise(point - Vec2::X * EPSILON, seed);
+    let d_dy = gradient_noise(point + Vec2::Y * EPSILON, seed)
+        - gradient_noise(point - Vec2::Y * EPSILON, seed);
+
+    Vec2::new(d_dy, -d_dx) / (2.0 * EPSILON)
+}
+
+/// Perlin style noise, between about -1 and 1
+fn gradient_noise(point: Vec2, seed: u32) -> f32 {
+    let cell = point.floor();
+    let fraction = point - cell;
+    let (x, y) = (cell.x as i32, cell.y as i32);
+
+    let corner = |dx: i32, dy: i32| {
+        let hash = hash(x + dx, y + dy, seed);
+        let gradient = Vec2::from_angle(hash as f32 / u32::MAX as f32 * TAU);
+        gradient.dot(fraction - Vec2::new(dx as f32, dy as f32))
+    };
+
+    // smoothstep, so the noise has no creases at cell edges
+    let blend = fraction * fraction * (3.0 - 2.0 * fraction);
+
+    let bottom = corner(0, 0) + (corner(1, 0) - corner(0, 0)) * blend.x;
+    let top = corner(0, 1) + (corner(1, 1) - corner(0, 1)) * blend.x;
+    bottom + (top - bottom) * blend.y
+}
+
+fn hash(x: i32, y: i32, seed: u32) -> u32 {
+    let mut hash = (x as u32).wrapping_mul(0x8da6_b343)
+        ^ (y as u32).wrapping_mul(0xd816_3841)
+        ^ seed.wrapping_mul(0xcb1a_b31f);
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(0x5bd1_e995);
+    hash ^ (hash >> 15)
+}
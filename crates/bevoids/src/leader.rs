@@ -0,0 +1,123 @@
+use bevy::{
+    prelude::{
+        Commands, Component, Entity, Event, EventWriter, Query, Res, ResMut, Resource, Vec3, With,
+    },
+    utils::HashMap,
+};
+
+use crate::{
+    analytics::FlockKey,
+    boids::{Boid, BoidCollisionGroup, BoidFlock, BoidSchool},
+    spatial::BoidSpatialIndex,
+};
+
+/// Makes a boid the leader of the boids around it.
+///
+/// Boids in the same flock, [BoidSchool] and collision group as the leader count it `weight` times
+/// when they align with and move towards their neighbours, so they follow it rather than their peers. The
+/// leader itself ignores its followers for alignment and cohesion, and only keeps its distance
+/// from them, so steer it with a [BoidGoal](crate::boids::BoidGoal), a custom behaviour or a
+/// [BoidSteering](crate::boids::BoidSteering).
+///
+/// If the leader despawns, the boid furthest ahead in its group takes over, and a
+/// [BoidLeaderElected] event is sent. Removing the component without despawning the boid just
+/// leaves the group without a leader.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct BoidLeader {
+    pub weight: f32,
+}
+
+impl BoidLeader {
+    pub fn new(weight: f32) -> Self {
+        Self { weight }
+    }
+}
+
+/// Sent when a boid takes over as leader because the last one despawned.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct BoidLeaderElected {
+    pub leader: Entity,
+    pub previous: Entity,
+}
+
+/// The leaders as of the last tick and the flock each one leads, so it's possible to tell when
+/// one disappears
+#[derive(Resource, Default)]
+pub(crate) struct BoidLeaders(HashMap<Entity, (FlockKey, BoidLeader)>);
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn elect_boid_leaders(
+    mut commands: Commands,
+    index: Res<BoidSpatialIndex>,
+    mut leaders: ResMut<BoidLeaders>,
+    leader_query: Query<(
+        Entity,
+        &BoidLeader,
+        Option<&BoidFlock>,
+        Option<&BoidSchool>,
+        Option<&BoidCollisionGroup>,
+    )>,
+    boids: Query<(), With<Boid>>,
+    mut elected: EventWriter<BoidLeaderElected>,
+) {
+    let mut current = leader_query
+        .iter()
+        .map(|(entity, leader, flock, school, collision_group)| {
+            let key = FlockKey {
+                flock: flock.map(|flock| flock.0),
+                school: school.copied(),
+                collision_group: collision_group.copied().unwrap_or(BoidCollisionGroup::ALL),
+            };
+            (entity, (key, *leader))
+        })
+        .collect::<HashMap<_, _>>();
+
+    for (previous, (group, leader)) in leaders.0.iter() {
+        // still around, or deliberately stepped down
+        if current.contains_key(previous) || boids.contains(*previous) {
+            continue;
+        }
+
+        // someone else already leads this group
+        if current
+            .values()
+            .any(|(other_group, _)| other_group == group)
+        {
+            continue;
+        }
+
+        let Some(successor) = find_successor(&index, *group) else {
+            continue;
+        };
+
+        // the component only arrives once the commands run, so count it as leading already
+        commands.entity(successor).try_insert(*leader);
+        current.insert(successor, (*group, *leader));
+        elected.send(BoidLeaderElected {
+            leader: successor,
+            previous: *previous,
+        });
+    }
+
+    leaders.0 = current;
+}
+
+/// Picks the boid furthest ahead in the direction the group is moving
+fn find_successor(index: &BoidSpatialIndex, group: FlockKey) -> Option<Entity> {
+    let members = index
+        .boids()
+        .iter()
+        .filter(|boid| FlockKey::of(boid) == group && boid.leader.is_none())
+        .collect::<Vec<_>>();
+
+    let heading = members
+        .iter()
+        .map(|boid| boid.velocity)
+        .sum::<Vec3>()
+        .normalize_or_zero();
+
+    members
+        .iter()
+        .max_by(|a, b| a.position.dot(heading).total_cmp(&b.position.dot(heading)))
+        .map(|boid| boid.entity)
+}
@@ -1,6 +1,22 @@
-#[allow(clippy::type_complexity)]
+pub mod analytics;
+pub mod behaviour;
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
 pub mod boids;
+pub mod flow;
+pub mod leader;
+pub mod obstacle;
+pub mod preset;
+pub mod spatial;
 
 pub mod prelude {
+    pub use super::analytics::{
+        FlockAnalytics, FlockEntered, FlockExited, FlockKey, FlockStats, FlockWatcher,
+    };
+    pub use super::behaviour::{BoidBehaviour, BoidBehaviourAppExt, BoidContext};
     pub use super::boids::*;
+    pub use super::flow::{FlowField, FlowGrid, FlowPattern};
+    pub use super::leader::{BoidLeader, BoidLeaderElected};
+    pub use super::obstacle::{BoidObstacle, BoidObstacleShape};
+    pub use super::preset::BoidPreset;
+    pub use super::spatial::BoidSpatialIndex;
 }
@@ -0,0 +1,186 @@
+use bevy::prelude::{Component, Quat, Vec2, Vec3};
+
+/// A solid shape that boids look ahead for and steer around before they hit it.
+///
+/// Unlike a [BoidRepulsor](crate::boids::BoidRepulsor), which pushes boids straight away from a
+/// point, boids cast a ray along their velocity and turn to the side when it passes through the
+/// obstacle, so they flow around long shapes instead of sliding off them at the last moment.
+///
+/// Add it to any entity with a transform. The shape follows the translation and rotation of the
+/// transform, but not its scale. The obstacle cannot also have a Boid component.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct BoidObstacle {
+    pub shape: BoidObstacleShape,
+    /// How strongly boids turn away from the obstacle
+    pub strength: f32,
+    /// How far ahead boids look for the obstacle, in seconds of travel at their current velocity
+    pub look_ahead: f32,
+    /// Extra space boids try to leave around the shape
+    pub margin: f32,
+}
+
+impl BoidObstacle {
+    pub fn new(shape: BoidObstacleShape, strength: f32, look_ahead: f32) -> Self {
+        Self {
+            shape,
+            strength,
+            look_ahead,
+            margin: 0.0,
+        }
+    }
+
+    /// Gets the steering force away from the obstacle for a boid, or zero if the boid won't hit it
+    /// within the look ahead time.
+    pub(crate) fn calc_avoidance(
+        &self,
+        translation: Vec3,
+        rotation: Quat,
+        position: Vec3,
+        velocity: Vec3,
+    ) -> Vec3 {
+        let inverse_rotation = rotation.inverse();
+        let local_position = inverse_rotation * (position - translation);
+        let local_velocity = inverse_rotation * velocity;
+
+        // already too close, just get out
+        if self.shape.distance(local_position) < self.margin {
+            return rotation * self.shape.normal(local_position) * self.strength;
+        }
+
+        let Some(direction) = local_velocity.try_normalize() else {
+            return Vec3::ZERO;
+        };
+        let probe_length = local_velocity.length() * self.look_ahead;
+
+        let Some(travelled) = self.ray_cast(local_position, direction, probe_length) else {
+            return Vec3::ZERO;
+        };
+
+        // turn sideways, away from the surface where the ray hits
+        let hit = local_position + direction * travelled;
+        let normal = self.shape.normal(hit);
+        let sideways = (normal - direction * normal.dot(direction))
+            .try_normalize()
+            // heading straight at the surface, so go round whichever side is closer
+            .or_else(|| (hit - direction * hit.dot(direction)).try_normalize())
+            .or_else(|| Vec3::new(-direction.y, direction.x, 0.0).try_normalize())
+            .unwrap_or_else(|| direction.any_orthonormal_vector());
+
+        // the closer the hit, the harder the turn
+        let urgency = 1.0 - travelled / probe_length;
+
+        rotation * sideways * self.strength * urgency
+    }
+
+    /// Marches along a ray until it comes within the margin of the shape, returning how far it
+    /// got, or `None` if it didn't hit within `max_distance`.
+    fn ray_cast(&self, origin: Vec3, direction: Vec3, max_distance: f32) -> Option<f32> {
+        const MAX_STEPS: usize = 32;
+        const MIN_STEP: f32 = 0.5;
+
+        let mut travelled = 0.0;
+
+        for _ in 0..MAX_STEPS {
+            let distance = self.shape.distance(origin + direction * travelled) - self.margin;
+            if distance <= 0.0 {
+                return Some(travelled);
+            }
+
+            travelled += distance.max(MIN_STEP);
+            if travelled > max_distance {
+                return None;
+            }
+        }
+
+        None
+    }
+}
+
+/// The shape of a [BoidObstacle], in the obstacle's local space.
+///
+/// Shapes are laid out in the xy plane. In 3d, circles become spheres, capsules are rounded in
+/// every direction, and rectangles stretch endlessly along the local z axis.
+#[derive(Clone, Copy, Debug)]
+pub enum BoidObstacleShape {
+    Circle {
+        radius: f32,
+    },
+    /// A line along the local y axis with rounded ends, e.g. a ship facing its direction of travel
+    Capsule {
+        radius: f32,
+        half_length: f32,
+    },
+    /// A rectangle that rotates with the transform
+    Rect {
+        half_size: Vec2,
+    },
+}
+
+impl BoidObstacleShape {
+    /// The distance from a local point to the surface of the shape, negative inside it
+    pub(crate) fn distance(&self, point: Vec3) -> f32 {
+        match *self {
+            BoidObstacleShape::Circle { radius } => point.length() - radius,
+            BoidObstacleShape::Capsule {
+                radius,
+                half_length,
+            } => {
+                let closest = Vec3::Y * point.y.clamp(-half_length, half_length);
+                point.distance(closest) - radius
+            }
+            BoidObstacleShape::Rect { half_size } => {
+                let outside = point.truncate().abs() - half_size;
+                outside.max(Vec2::ZERO).length() + outside.max_element().min(0.0)
+            }
+        }
+    }
+
+    /// The direction away from the surface at a local point
+    fn normal(&self, point: Vec3) -> Vec3 {
+        const EPSILON: f32 = 0.01;
+
+        let gradient = Vec3::new(
+            self.distance(point + Vec3::X * EPSILON) - self.distance(point - Vec3::X * EPSILON),
+            self.distance(point + Vec3::Y * EPSILON) - self.distance(point - Vec3::Y * EPSILON),
+            self.distance(point + Vec3::Z * EPSILON) - self.distance(point - Vec3::Z * EPSILON),
+        );
+
+        gradient.try_normalize().unwrap_or(Vec3::Y)
+    }
+
+    /// Points around the edge of the shape in the local xy plane, for drawing it
+    pub(crate) fn outline(&self) -> Vec<Vec2> {
+        const SEGMENTS: usize = 32;
+
+        let arc = |centre: Vec2, radius: f32, start: f32, end: f32| {
+            (0..=SEGMENTS).map(move |step| {
+                let angle = start + (end - start) * step as f32 / SEGMENTS as f32;
+                centre + Vec2::from_angle(angle) * radius
+            })
+        };
+
+        match *self {
+            BoidObstacleShape::Circle { radius } => {
+                arc(Vec2::ZERO, radius, 0.0, std::f32::consts::TAU).collect()
+            }
+            BoidObstacleShape::Capsule {
+                radius,
+                half_length,
+            } => {
+                let pi = std::f32::consts::PI;
+                let mut points = arc(Vec2::Y * half_length, radius, 0.0, pi)
+                    .chain(arc(-Vec2::Y * half_length, radius, pi, 2.0 * pi))
+                    .collect::<Vec<_>>();
+                points.push(points[0]);
+                points
+            }
+            BoidObstacleShape::Rect { half_size } => vec![
+                Vec2::new(-half_size.x, -half_size.y),
+                Vec2::new(half_size.x, -half_size.y),
+                Vec2::new(half_size.x, half_size.y),
+                Vec2::new(-half_size.x, half_size.y),
+                Vec2::new(-half_size.x, -half_size.y),
+            ],
+        }
+    }
+}
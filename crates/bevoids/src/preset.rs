@@ -0,0 +1,276 @@
+use std::fmt;
+
+use bevy::{
+    asset::{
+        io::Reader, Asset, AssetEvent, AssetLoader, Assets, AsyncReadExt, Handle, LoadContext,
+    },
+    prelude::{Commands, DetectChanges, Entity, EventReader, Query, Ref, Res, Without},
+    reflect::TypePath,
+    utils::HashSet,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::boids::{
+    Boid, BoidAgility, BoidCollisionGroup, BoidFlock, BoidJitter, BoidSpeed, BoidTurningStrength,
+    BoidViewConfig, Flock,
+};
+
+/// Tuning for a boid, loaded from a `.boid.ron` file so it can be changed without recompiling.
+///
+/// Add the `Handle<BoidPreset>` to a [Boid] or a [Flock], and the preset is applied once it
+/// has loaded, and again whenever the file changes (if bevy's `file_watcher` feature is on).
+/// A flock's preset also applies to the boids in the flock that don't have a preset of their
+/// own, as they join and whenever it changes.
+///
+/// ```ron
+/// (
+///     speed: (min: 20.0, max: 30.0),
+///     turning_strength: (coherence: 0.25, separation: 0.5, alignment: 0.08, border: 200.0),
+///     view_config: (fov: 240, protected_range: 15.0, view_range: 120.0),
+///     agility: (max_turn_rate: Some(3.0), max_acceleration: Some(60.0)),
+///     jitter: Some(1.3),
+///     collision_group: None,
+/// )
+/// ```
+#[derive(Asset, TypePath, Clone, Debug, Serialize, Deserialize)]
+pub struct BoidPreset {
+    pub speed: BoidSpeed,
+    pub turning_strength: BoidTurningStrength,
+    pub view_config: BoidViewConfig,
+    /// Turn rate and acceleration limits. Boids have no limits if this is left out.
+    #[serde(default)]
+    pub agility: BoidAgility,
+    /// The [BoidJitter] to give the boid. Boids lose their jitter if this is `None`.
+    #[serde(default)]
+    pub jitter: Option<f32>,
+    /// The bits of the [BoidCollisionGroup] to put the boid in. Boids keep their own collision
+    /// group if this is `None`.
+    #[serde(default)]
+    pub collision_group: Option<u32>,
+}
+
+/// Middle of the road tuning, for when a preset can't be loaded
+impl Default for BoidPreset {
+    fn default() -> Self {
+        Self {
+            speed: BoidSpeed::new(20.0, 30.0),
+            turning_strength: BoidTurningStrength::new(0.25, 0.5, 0.08, 200.0),
+            view_config: BoidViewConfig::new(240, 15.0, 120.0),
+            agility: BoidAgility::default(),
+            jitter: None,
+            collision_group: None,
+        }
+    }
+}
+
+/// Loads [BoidPreset]s from `.boid.ron` files
+#[derive(Default)]
+pub struct BoidPresetLoader;
+
+/// The errors that can happen while loading a [BoidPreset]
+#[derive(Debug)]
+pub enum BoidPresetLoaderError {
+    Io(std::io::Error),
+    Ron(ron::error::SpannedError),
+}
+
+impl fmt::Display for BoidPresetLoaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BoidPresetLoaderError::Io(err) => write!(f, "could not read boid preset: {err}"),
+            BoidPresetLoaderError::Ron(err) => write!(f, "could not parse boid preset: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for BoidPresetLoaderError {}
+
+impl From<std::io::Error> for BoidPresetLoaderError {
+    fn from(err: std::io::Error) -> Self {
+        BoidPresetLoaderError::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for BoidPresetLoaderError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        BoidPresetLoaderError::Ron(err)
+    }
+}
+
+impl AssetLoader for BoidPresetLoader {
+    type Asset = BoidPreset;
+    type Settings = ();
+    type Error = BoidPresetLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["boid.ron"]
+    }
+}
+
+/// Applies presets to boids and flocks when the preset loads or changes, or when the handle is
+/// first added to an entity.
+#[allow(clippy::type_complexity)]
+pub(crate) fn apply_boid_presets(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<BoidPreset>>,
+    presets: Res<Assets<BoidPreset>>,
+    mut boids: Query<(Entity, Ref<Handle<BoidPreset>>, &mut Boid)>,
+    mut flocks: Query<(Entity, Ref<Handle<BoidPreset>>, &mut Flock), Without<Boid>>,
+    mut members: Query<(Entity, Ref<BoidFlock>, &mut Boid), Without<Handle<BoidPreset>>>,
+) {
+    let changed = events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => Some(*id),
+            _ => None,
+        })
+        .collect::<HashSet<_>>();
+
+    for (entity, handle, mut boid) in boids.iter_mut() {
+        if !handle.is_added() && !changed.contains(&handle.id()) {
+            continue;
+        }
+
+        let Some(preset) = presets.get(&*handle) else {
+            continue;
+        };
+
+        apply_to_boid(&mut commands, entity, &mut boid, preset);
+    }
+
+    let mut changed_flocks = HashSet::default();
+    for (entity, handle, mut flock) in flocks.iter_mut() {
+        if !handle.is_added() && !changed.contains(&handle.id()) {
+            continue;
+        }
+
+        let Some(preset) = presets.get(&*handle) else {
+            continue;
+        };
+
+        flock.speed = preset.speed;
+        flock.turning_strength = preset.turning_strength;
+        flock.view_config = preset.view_config;
+        flock.agility = preset.agility;
+        changed_flocks.insert(entity);
+    }
+
+    // the flock only keeps defaults for new boids, so pass the whole preset on to its members
+    for (entity, flock, mut boid) in members.iter_mut() {
+        if !flock.is_added() && !changed_flocks.contains(&flock.0) {
+            continue;
+        }
+
+        let Some(preset) = flocks
+            .get(flock.0)
+            .ok()
+            .and_then(|(_, handle, _)| presets.get(&*handle))
+        else {
+            continue;
+        };
+
+        apply_to_boid(&mut commands, entity, &mut boid, preset);
+    }
+}
+
+fn apply_to_boid(commands: &mut Commands, entity: Entity, boid: &mut Boid, preset: &BoidPreset) {
+    boid.speed = preset.speed;
+    boid.turning_strength = preset.turning_strength;
+    boid.view_config = preset.view_config;
+    boid.agility = preset.agility;
+
+    let mut entity_commands = commands.entity(entity);
+    match preset.jitter {
+        Some(jitter) => entity_commands.insert(BoidJitter(jitter)),
+        None => entity_commands.remove::<BoidJitter>(),
+    };
+
+    if let Some(bits) = preset.collision_group {
+        entity_commands.insert(BoidCollisionGroup::from_bits_truncate(bits));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        app::App,
+        app::Update,
+        asset::{AssetApp, AssetPlugin},
+        MinimalPlugins,
+    };
+
+    use super::*;
+    use crate::boids::BoidSpace;
+
+    #[test]
+    fn reloading_a_flock_preset_updates_its_boids() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<BoidPreset>()
+            .add_systems(Update, apply_boid_presets);
+
+        let preset = BoidPreset {
+            jitter: Some(1.0),
+            ..Default::default()
+        };
+        let handle = app
+            .world_mut()
+            .resource_mut::<Assets<BoidPreset>>()
+            .add(preset.clone());
+
+        let flock = Flock::new(
+            BoidSpace::TwoDimensional,
+            preset.speed,
+            preset.turning_strength,
+            preset.view_config,
+        );
+        let boid = flock.boid();
+        let flock = app.world_mut().spawn((flock, handle.clone())).id();
+        let member = app.world_mut().spawn((boid, BoidFlock(flock))).id();
+
+        app.update();
+        assert_eq!(
+            app.world().get::<BoidJitter>(member).map(|jitter| jitter.0),
+            Some(1.0)
+        );
+
+        let mut presets = app.world_mut().resource_mut::<Assets<BoidPreset>>();
+        let reloaded = presets.get_mut(&handle).unwrap();
+        reloaded.jitter = Some(2.0);
+        reloaded.view_config.view_range = 60.0;
+        reloaded.collision_group = Some(0b100);
+
+        // the change is announced at the end of one frame and picked up in the next
+        app.update();
+        app.update();
+
+        let world = app.world();
+        assert_eq!(
+            world.get::<Flock>(flock).unwrap().view_config.view_range,
+            60.0
+        );
+        assert_eq!(
+            world.get::<Boid>(member).unwrap().view_config.view_range,
+            60.0
+        );
+        assert_eq!(
+            world.get::<BoidJitter>(member).map(|jitter| jitter.0),
+            Some(2.0)
+        );
+        assert_eq!(
+            world.get::<BoidCollisionGroup>(member).copied(),
+            Some(BoidCollisionGroup::from_bits_truncate(0b100))
+        );
+    }
+}
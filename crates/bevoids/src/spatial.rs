@@ -0,0 +1,391 @@
+use bevy::{
+    prelude::{Entity, IVec3, Resource, Vec3},
+    tasks::{ComputeTaskPool, ParallelSlice, TaskPool},
+    utils::HashMap,
+};
+
+use crate::{
+    boids::{BoidCollisionGroup, BoidSchool, BoidSpace, BoidViewConfig},
+    leader::BoidLeader,
+};
+
+/// A snapshot of a single boid, taken when the [BoidSpatialIndex] is rebuilt.
+#[derive(Clone, Copy)]
+pub struct IndexedBoid {
+    pub entity: Entity,
+    pub position: Vec3,
+    pub velocity: Vec3,
+    pub collision_group: Option<BoidCollisionGroup>,
+    pub flock: Option<Entity>,
+    pub school: Option<BoidSchool>,
+    pub view_config: BoidViewConfig,
+    pub space: BoidSpace,
+    pub leader: Option<BoidLeader>,
+    /// How many ticks of steering the boid catches up on this tick, see
+    /// [BoidLod](crate::boids::BoidLod). Boids that are only coasting along this tick have 0.
+    pub steer_ticks: u32,
+}
+
+/// Empty cells are kept between rebuilds so their allocations can be reused, until there are
+/// this many times more cells than boids
+const MAX_CELLS_PER_BOID: usize = 4;
+
+/// A uniform grid over all boids, rebuilt at the start of every boid update.
+///
+/// The cell size is the largest `view_range` of any boid, so a neighbour search only ever has
+/// to look at the cells directly around a boid instead of every other boid in the world.
+#[derive(Resource, Default)]
+pub struct BoidSpatialIndex {
+    cell_size: f32,
+    cells: HashMap<IVec3, Vec<usize>>,
+    boids: Vec<IndexedBoid>,
+    entities: HashMap<Entity, usize>,
+    /// The neighbours of every boid, one after another
+    neighbours: Vec<usize>,
+    /// Where each boid's neighbours end in `neighbours`
+    neighbour_ends: Vec<usize>,
+}
+
+impl BoidSpatialIndex {
+    /// Replaces the contents of the index with the given boids.
+    ///
+    /// `cell_size` should be the largest view range of any boid in `boids`.
+    pub fn rebuild(&mut self, boids: impl IntoIterator<Item = IndexedBoid>, cell_size: f32) {
+        self.cell_size = cell_size.max(f32::EPSILON);
+        self.boids.clear();
+        self.boids.extend(boids);
+        self.neighbours.clear();
+        self.neighbour_ends.clear();
+
+        self.entities.clear();
+        self.entities.extend(
+            self.boids
+                .iter()
+                .enumerate()
+                .map(|(idx, boid)| (boid.entity, idx)),
+        );
+
+        // a flock roaming across the world leaves a trail of empty cells behind it
+        if self.cells.len() > self.boids.len().max(1) * MAX_CELLS_PER_BOID {
+            self.cells.retain(|_, cell| !cell.is_empty());
+        }
+
+        // keep the allocations around, they'll most likely be reused next tick
+        for cell in self.cells.values_mut() {
+            cell.clear();
+        }
+
+        for (idx, boid) in self.boids.iter().enumerate() {
+            let cell = Self::cell_of(boid.position, self.cell_size);
+            self.cells.entry(cell).or_default().push(idx);
+        }
+    }
+
+    /// All the boids in the index, in the order they were inserted
+    pub fn boids(&self) -> &[IndexedBoid] {
+        &self.boids
+    }
+
+    /// Gets the position of a boid in [BoidSpatialIndex::boids], if it was in the index when it
+    /// was last rebuilt
+    pub fn index_of(&self, entity: Entity) -> Option<usize> {
+        self.entities.get(&entity).copied()
+    }
+
+    /// The neighbours a boid reacts to this tick, as indices into [BoidSpatialIndex::boids].
+    ///
+    /// These are the boids in the same flock and collision group that are within view range
+    /// and the field of view, limited to the nearest few in topological mode. Boids that aren't
+    /// steered this tick have none. They're sorted, so summing over them gives the same result
+    /// as looping over every boid in insertion order.
+    pub fn neighbours(&self, idx: usize) -> &[usize] {
+        let start = match idx {
+            0 => 0,
+            _ => self
+                .neighbour_ends
+                .get(idx - 1)
+                .copied()
+                .unwrap_or_default(),
+        };
+
+        match self.neighbour_ends.get(idx) {
+            Some(end) => &self.neighbours[start..*end],
+            None => &[],
+        }
+    }
+
+    /// Works out the neighbours of every boid in the index, in parallel
+    pub(crate) fn find_neighbours(&mut self) {
+        let indices = (0..self.boids.len()).collect::<Vec<_>>();
+        let task_pool = ComputeTaskPool::get_or_init(TaskPool::default);
+
+        // each chunk shares its scratch space between its boids, and hands back its neighbours
+        // along with where each boid's neighbours end
+        let chunks = indices.par_splat_map(task_pool, None, |_, chunk| {
+            let mut visible = Vec::new();
+            let mut neighbours = Vec::new();
+            let mut ends = Vec::with_capacity(chunk.len());
+
+            for idx in chunk {
+                self.visible_neighbours(*idx, &mut visible, &mut neighbours);
+                ends.push(neighbours.len());
+            }
+
+            (neighbours, ends)
+        });
+
+        for (neighbours, ends) in chunks {
+            let offset = self.neighbours.len();
+            self.neighbours.extend_from_slice(&neighbours);
+            self.neighbour_ends
+                .extend(ends.into_iter().map(|end| offset + end));
+        }
+    }
+
+    /// Adds the neighbours a boid can see to the end of `neighbours`. `visible` is scratch space.
+    fn visible_neighbours(
+        &self,
+        idx: usize,
+        visible: &mut Vec<(f32, usize)>,
+        neighbours: &mut Vec<usize>,
+    ) {
+        let boid = &self.boids[idx];
+
+        // nobody needs the neighbours of a boid that isn't being steered
+        if boid.steer_ticks == 0 {
+            return;
+        }
+        let view_config = &boid.view_config;
+        let heading = boid.space.project(boid.velocity);
+
+        visible.clear();
+        for neighbour_idx in self.candidates(boid.position, view_config.view_range) {
+            let neighbour = &self.boids[neighbour_idx];
+
+            if neighbour_idx == idx || neighbour.flock != boid.flock {
+                continue;
+            }
+
+            if let (Some(group), Some(neighbour_group)) =
+                (boid.collision_group, neighbour.collision_group)
+            {
+                if !group.intersects(neighbour_group) {
+                    continue;
+                }
+            }
+
+            let distance = boid.position.distance(neighbour.position);
+
+            if distance <= view_config.view_range
+                && view_config.in_fov(
+                    heading,
+                    boid.space.project(neighbour.position - boid.position),
+                )
+            {
+                visible.push((distance, neighbour_idx));
+            }
+        }
+
+        // in topological mode only the nearest few visible boids count
+        if let Some(nearest) = view_config.nearest {
+            if visible.len() > nearest {
+                visible.sort_by(|(a_distance, a_idx), (b_distance, b_idx)| {
+                    a_distance.total_cmp(b_distance).then(a_idx.cmp(b_idx))
+                });
+                visible.truncate(nearest);
+            }
+        }
+
+        // the candidates come cell by cell, so put them back in insertion order
+        visible.sort_unstable_by_key(|(_, idx)| *idx);
+        neighbours.extend(visible.iter().map(|(_, idx)| *idx));
+    }
+
+    /// The width of a grid cell, which is the largest view range of any boid
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// Gets the index of every boid that could be within `range` of `position`.
+    ///
+    /// This is a superset of the boids in range, so callers still have to do a distance check.
+    /// The indices come a grid cell at a time, so they aren't sorted.
+    pub fn candidates(&self, position: Vec3, range: f32) -> impl Iterator<Item = usize> + '_ {
+        let centre = Self::cell_of(position, self.cell_size);
+        let reach = match self.boids.is_empty() {
+            true => -1,
+            false => (range / self.cell_size).ceil().max(1.0) as i32,
+        };
+
+        (-reach..=reach)
+            .flat_map(move |x| {
+                (-reach..=reach)
+                    .flat_map(move |y| (-reach..=reach).map(move |z| IVec3::new(x, y, z)))
+            })
+            .filter_map(move |offset| self.cells.get(&(centre + offset)))
+            .flatten()
+            .copied()
+    }
+
+    fn cell_of(position: Vec3, cell_size: f32) -> IVec3 {
+        (position / cell_size).floor().as_ivec3()
+    }
+
+    /// Finds the neighbours of a boid by checking every other boid, as a reference for the grid
+    #[cfg(test)]
+    pub(crate) fn brute_force_neighbours(&self, idx: usize) -> Vec<usize> {
+        let boid = &self.boids[idx];
+        let heading = boid.space.project(boid.velocity);
+
+        let mut visible = self
+            .boids
+            .iter()
+            .enumerate()
+            .filter(|(neighbour_idx, neighbour)| {
+                *neighbour_idx != idx
+                    && neighbour.flock == boid.flock
+                    && boid
+                        .collision_group
+                        .zip(neighbour.collision_group)
+                        .is_none_or(|(a, b)| a.intersects(b))
+                    && boid.view_config.in_fov(
+                        heading,
+                        boid.space.project(neighbour.position - boid.position),
+                    )
+            })
+            .map(|(neighbour_idx, neighbour)| {
+                (boid.position.distance(neighbour.position), neighbour_idx)
+            })
+            .filter(|(distance, _)| *distance <= boid.view_config.view_range)
+            .collect::<Vec<_>>();
+
+        if let Some(nearest) = boid.view_config.nearest {
+            visible.sort_by(|(a_distance, a_idx), (b_distance, b_idx)| {
+                a_distance.total_cmp(b_distance).then(a_idx.cmp(b_idx))
+            });
+            visible.truncate(nearest);
+        }
+
+        let mut neighbours = visible.into_iter().map(|(_, idx)| idx).collect::<Vec<_>>();
+        neighbours.sort_unstable();
+        neighbours
+    }
+}
+
+/// A seeded jumble of boids in two flocks, a few collision groups and both spaces, some of them
+/// only looking at their nearest neighbours
+#[cfg(test)]
+pub(crate) fn random_boids(seed: u64, count: usize) -> Vec<IndexedBoid> {
+    use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let groups = [
+        None,
+        Some(BoidCollisionGroup::GROUP_1),
+        Some(BoidCollisionGroup::GROUP_2),
+        Some(BoidCollisionGroup::GROUP_1 | BoidCollisionGroup::GROUP_2),
+    ];
+
+    (0..count)
+        .map(|idx| {
+            let space = match rng.gen_bool(0.5) {
+                true => BoidSpace::TwoDimensional,
+                false => BoidSpace::ThreeDimensional,
+            };
+            let mut view_config = BoidViewConfig::new(
+                *[120, 240, 360].choose(&mut rng).unwrap(),
+                rng.gen_range(5.0..15.0),
+                rng.gen_range(20.0..60.0),
+            );
+            if rng.gen_bool(0.3) {
+                view_config = view_config.with_nearest(rng.gen_range(1..8));
+            }
+
+            let random_vec = |rng: &mut StdRng, extent: f32| {
+                space.project(Vec3::new(
+                    rng.gen_range(-extent..extent),
+                    rng.gen_range(-extent..extent),
+                    rng.gen_range(-extent..extent),
+                ))
+            };
+
+            IndexedBoid {
+                entity: Entity::from_raw(idx as u32),
+                position: random_vec(&mut rng, 100.0),
+                velocity: random_vec(&mut rng, 30.0),
+                collision_group: *groups.choose(&mut rng).unwrap(),
+                flock: Some(Entity::from_raw(10_000 + rng.gen_range(0..2))),
+                school: None,
+                view_config,
+                space,
+                leader: None,
+                steer_ticks: 1,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grid_neighbours_match_brute_force() {
+        for seed in 0..8 {
+            let boids = random_boids(seed, 400);
+            let cell_size = boids
+                .iter()
+                .map(|boid| boid.view_config.view_range)
+                .fold(0.0, f32::max);
+
+            let mut index = BoidSpatialIndex::default();
+            index.rebuild(boids, cell_size);
+            index.find_neighbours();
+
+            let mut found = 0;
+            for idx in 0..index.boids().len() {
+                let neighbours = index.neighbours(idx);
+                assert_eq!(
+                    neighbours,
+                    index.brute_force_neighbours(idx),
+                    "seed {seed}, boid {idx}"
+                );
+                found += neighbours.len();
+            }
+
+            // make sure the flock is crowded enough to be worth checking
+            assert!(
+                found > index.boids().len(),
+                "seed {seed} only found {found}"
+            );
+        }
+    }
+
+    #[test]
+    fn rebuilding_reuses_cells() {
+        let mut index = BoidSpatialIndex::default();
+        let boids = random_boids(1, 50);
+
+        index.rebuild(boids.clone(), 60.0);
+        let cells = index.cells.len();
+
+        // moving every boid far away leaves the old cells empty, but still allocated
+        let moved = boids.iter().map(|boid| IndexedBoid {
+            position: boid.position + Vec3::splat(10_000.0),
+            ..*boid
+        });
+        index.rebuild(moved, 60.0);
+        assert!(index.cells.len() > cells);
+        assert!(index.cells.values().filter(|cell| cell.is_empty()).count() >= cells);
+
+        // but not forever
+        for step in 2..20 {
+            let moved = boids.iter().map(|boid| IndexedBoid {
+                position: boid.position + Vec3::splat(10_000.0 * step as f32),
+                ..*boid
+            });
+            index.rebuild(moved, 60.0);
+        }
+        assert!(index.cells.len() <= (boids.len() * (MAX_CELLS_PER_BOID + 1)));
+    }
+}
@@ -1,10 +1,109 @@
 //! Development tools for the game. This plugin is only enabled in dev builds.
 
-use bevy::{dev_tools::states::log_transitions, prelude::*};
+use bevoids::boids::BoidDebug;
+use bevy::{
+    dev_tools::states::log_transitions, input::common_conditions::input_just_pressed, prelude::*,
+};
 
-use crate::screen::Screen;
+use crate::{
+    game::spawn::{creature::fish::Fish, encounters::director::EncounterDirector},
+    screen::Screen,
+};
 
 pub(super) fn plugin(app: &mut App) {
     // Print state transitions in dev builds
     app.add_systems(Update, log_transitions::<Screen>);
+
+    // Toggle boid debug overlays on fish, for tuning the boid constants
+    app.init_resource::<FishDebugEnabled>().add_systems(
+        Update,
+        (
+            toggle_fish_debug.run_if(input_just_pressed(KeyCode::F3)),
+            add_fish_debug,
+        )
+            .chain(),
+    );
+
+    // Toggle a readout of what the encounter director is doing, for tuning the encounter budget
+    app.add_systems(
+        Update,
+        (
+            toggle_director_readout.run_if(input_just_pressed(KeyCode::F4)),
+            update_director_readout,
+        )
+            .chain(),
+    );
+}
+
+#[derive(Resource, Default)]
+struct FishDebugEnabled(bool);
+
+fn toggle_fish_debug(
+    mut commands: Commands,
+    mut enabled: ResMut<FishDebugEnabled>,
+    fish: Query<Entity, With<Fish>>,
+) {
+    enabled.0 = !enabled.0;
+
+    for entity in &fish {
+        if enabled.0 {
+            commands.entity(entity).insert(BoidDebug::all());
+        } else {
+            commands.entity(entity).remove::<BoidDebug>();
+        }
+    }
+}
+
+fn add_fish_debug(
+    mut commands: Commands,
+    enabled: Res<FishDebugEnabled>,
+    fish: Query<Entity, Added<Fish>>,
+) {
+    if !enabled.0 {
+        return;
+    }
+
+    for entity in &fish {
+        commands.entity(entity).insert(BoidDebug::all());
+    }
+}
+
+#[derive(Component)]
+struct DirectorReadout;
+
+fn toggle_director_readout(mut commands: Commands, readouts: Query<Entity, With<DirectorReadout>>) {
+    if readouts.is_empty() {
+        commands.spawn((
+            Name::new("Director Readout"),
+            DirectorReadout,
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font_size: 14.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(8.),
+                right: Val::Px(8.),
+                ..default()
+            })
+            .with_background_color(Color::BLACK.with_alpha(0.6)),
+        ));
+    } else {
+        for entity in &readouts {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+fn update_director_readout(
+    director: Res<EncounterDirector>,
+    mut readouts: Query<&mut Text, With<DirectorReadout>>,
+) {
+    for mut text in &mut readouts {
+        text.sections[0].value = director.to_string();
+    }
 }
@@ -1,10 +1,14 @@
+use bevoids::preset::BoidPreset;
 use bevy::{
+    asset::{LoadState, RecursiveDependencyLoadState},
     prelude::*,
     render::texture::{ImageLoaderSettings, ImageSampler},
     utils::HashMap,
 };
 use serde::{Deserialize, Serialize};
 
+use super::spawn::{encounters::table::EncounterTable, timeline::Timelines};
+
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<HandleMap<ImageKey>>();
     app.init_resource::<HandleMap<ImageKey>>();
@@ -139,7 +143,7 @@ impl FromWorld for HandleMap<ImageKey> {
     }
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Reflect)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Reflect, Debug, Serialize, Deserialize)]
 pub enum SfxKey {
     ButtonHover,
     ButtonPress,
@@ -238,6 +242,76 @@ impl FromWorld for HandleMap<SoundtrackKey> {
     }
 }
 
+/// Boid tuning that can be edited while the game is running.
+///
+/// The handle map is initialised by the boid plugin, as the asset type is only registered once
+/// `BoidsPlugin` has been added.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Reflect, Debug, Serialize, Deserialize)]
+pub enum BoidPresetKey {
+    Fish,
+}
+
+impl AssetKey for BoidPresetKey {
+    type Asset = BoidPreset;
+}
+
+impl FromWorld for HandleMap<BoidPresetKey> {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+        [(
+            BoidPresetKey::Fish,
+            asset_server.load("boids/fish.boid.ron"),
+        )]
+        .into()
+    }
+}
+
+/// Encounter spawn rates that can be balanced while the game is running.
+///
+/// The handle map is initialised by the encounter plugin, which registers the asset type.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Reflect, Debug, Serialize, Deserialize)]
+pub enum EncounterTableKey {
+    Migration,
+}
+
+impl AssetKey for EncounterTableKey {
+    type Asset = EncounterTable;
+}
+
+impl FromWorld for HandleMap<EncounterTableKey> {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+        [(
+            EncounterTableKey::Migration,
+            asset_server.load("encounters/migration.encounters.ron"),
+        )]
+        .into()
+    }
+}
+
+/// Scripted set pieces that play at points in the migration.
+///
+/// The handle map is initialised by the timeline plugin, which registers the asset type.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Reflect, Debug, Serialize, Deserialize)]
+pub enum TimelineKey {
+    Migration,
+}
+
+impl AssetKey for TimelineKey {
+    type Asset = Timelines;
+}
+
+impl FromWorld for HandleMap<TimelineKey> {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+        [(
+            TimelineKey::Migration,
+            asset_server.load("timelines/migration.timeline.ron"),
+        )]
+        .into()
+    }
+}
+
 pub trait AssetKey: Sized {
     type Asset: Asset;
 }
@@ -256,8 +330,16 @@ where
 }
 
 impl<K: AssetKey> HandleMap<K> {
+    /// Returns true once every asset has either loaded or failed to. The asset server logs the
+    /// failures, and it's up to whoever uses the asset to fall back to something sensible.
     pub fn all_loaded(&self, asset_server: &AssetServer) -> bool {
-        self.values()
-            .all(|x| asset_server.is_loaded_with_dependencies(x))
+        self.values().all(|x| {
+            asset_server.is_loaded_with_dependencies(x)
+                || matches!(asset_server.get_load_state(x), Some(LoadState::Failed(_)))
+                || matches!(
+                    asset_server.get_recursive_dependency_load_state(x),
+                    Some(RecursiveDependencyLoadState::Failed)
+                )
+        })
     }
 }
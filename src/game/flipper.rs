@@ -10,7 +10,7 @@ use crate::{
 use super::{
     spawn::{
         creature::Creature,
-        encounters::EncounterTimers,
+        encounters::{director::EncounterDirector, EncounterTimers},
         player::{Whale, WhaleRotation},
         WindowSize,
     },
@@ -164,6 +164,7 @@ fn update_flip_timer(
     mut is_flipped: ResMut<IsFlipped>,
     mut distance: ResMut<TravelDistance>,
     mut encounters: ResMut<EncounterTimers>,
+    mut director: ResMut<EncounterDirector>,
     mut raininess: ResMut<Raininess>,
     mut weather: ResMut<WeatherState>,
     mut whale_rot: ResMut<WhaleRotation>,
@@ -182,7 +183,8 @@ fn update_flip_timer(
             // reset all the flippin' state
             is_flipped.toggle();
             distance.reset_timer();
-            encounters.reset(distance.travel_direction());
+            encounters.reset();
+            director.reset();
             raininess.reset();
             weather.time_of_day = INITIAL_TIME_OF_DAY;
 
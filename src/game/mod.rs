@@ -7,6 +7,7 @@ pub mod assets;
 pub mod audio;
 mod flipper;
 mod movement;
+pub mod rng;
 pub mod spawn;
 mod weather;
 
@@ -17,6 +18,7 @@ pub(super) fn plugin(app: &mut App) {
         assets::plugin,
         flipper::plugin,
         movement::plugin,
+        rng::plugin,
         spawn::plugin,
         weather::plugin,
     ));
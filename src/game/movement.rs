@@ -3,6 +3,7 @@
 //! If you want to move the player in a smoother way,
 //! consider using a [fixed timestep](https://github.com/bevyengine/bevy/blob/latest/examples/movement/physics_in_fixed_timestep.rs).
 
+use bevoids::flow::FlowField;
 use bevy::prelude::*;
 
 use crate::{screen::Screen, AppSet};
@@ -38,6 +39,11 @@ pub struct ArrivedAtLocation;
 #[derive(Component)]
 pub struct MoveWithVelocity(pub Vec3);
 
+/// Carries an entity with [`MoveWithVelocity`] along with the ocean currents, the same way
+/// they carry the fish
+#[derive(Component)]
+pub struct DriftWithCurrent;
+
 /// Denotes a component that rotates to face the direction of travel
 /// This is done in the [`move_towards_location`] system.
 #[derive(Component)]
@@ -233,15 +239,24 @@ fn move_towards_location(
 
 /// Moves entities that have the [`MoveWithVelocity`] component in their direction of travel
 fn move_with_velocity(
+    time: Res<Time>,
+    flow: Option<Res<FlowField>>,
     mut movers: Query<(
         &mut Transform,
         &MoveWithVelocity,
         Option<&RotateToFaceMovement>,
+        Has<DriftWithCurrent>,
     )>,
 ) {
-    for (mut mover, details, rotate_to_face) in &mut movers {
+    for (mut mover, details, rotate_to_face, drifts) in &mut movers {
         let prev = mover.translation;
         mover.translation += details.0;
+
+        if let Some(flow) = flow.as_ref().filter(|_| drifts) {
+            // currents are per second, unlike the velocity
+            mover.translation += flow.sample(prev) * time.delta_seconds();
+        }
+
         mover.translation.z = prev.z; // keep z-height
 
         if rotate_to_face.is_some() {
@@ -0,0 +1,150 @@
+//! Seeded randomness, so a voyage can be replayed from its seed.
+
+use bevoids::boids::BoidRng;
+use bevy::{prelude::*, utils::HashMap};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::screen::Screen;
+
+/// Runs when a new voyage starts, before any `OnEnter(Screen::Playing)` systems. Anything the
+/// [VoyageSeed] decides is reset here, so a voyage doesn't carry on from where the last left off.
+pub const NEW_VOYAGE: OnTransition<Screen> = OnTransition {
+    exited: Screen::Title,
+    entered: Screen::Playing,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<GameRng>();
+    app.init_resource::<VoyageSeed>();
+
+    app.add_systems(NEW_VOYAGE, seed_game_rng);
+
+    app.configure_sets(
+        FixedUpdate,
+        (
+            VoyageSet::Travel,
+            VoyageSet::Weather,
+            VoyageSet::Encounters,
+            VoyageSet::Timelines,
+        )
+            .chain()
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+/// The parts of a voyage that move on each [FixedUpdate] tick, in the order they run. Stepping
+/// them by the fixed timestep means the same seed replays the same voyage at any frame rate.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VoyageSet {
+    /// How far the whale has travelled
+    Travel,
+    /// The time of day, sunshine and rain
+    Weather,
+    /// Encounters from the encounter table
+    Encounters,
+    /// Scripted timelines
+    Timelines,
+}
+
+/// The parts of the game that draw random numbers. Each gets its own stream, so extra draws in
+/// one (e.g. more waves at a higher frame rate) don't change what happens in the others.
+///
+/// Draws that affect the voyage should happen a fixed number of times, e.g. once per spawn or
+/// once per [FixedUpdate] tick, rather than once per frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RngStream {
+    /// When encounters spawn
+    Encounters,
+    /// Where creatures go and how they behave
+    Creatures,
+    /// Sunshine and currents
+    Weather,
+    /// The player whale
+    Player,
+    /// Waves, rain drops and snow, which don't affect the game
+    Effects,
+    /// When it starts and stops raining
+    Rain,
+    /// Creatures getting curious about the whale and following it around
+    Curiosity,
+    /// Seeds the boid simulation
+    Boids,
+}
+
+/// The random number generator for everything that happens on a voyage.
+///
+/// It's reseeded at the start of each voyage, from the [VoyageSeed] if one was entered or at
+/// random otherwise, so the same seed gives the same encounters, weather and currents.
+#[derive(Resource)]
+pub struct GameRng {
+    seed: u64,
+    streams: HashMap<RngStream, StdRng>,
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::new(rand::thread_rng().gen())
+    }
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            streams: HashMap::default(),
+        }
+    }
+
+    /// The seed the current voyage was started with
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Gets the random number generator for part of the game
+    pub fn stream(&mut self, stream: RngStream) -> &mut StdRng {
+        let seed = self.seed;
+        self.streams.entry(stream).or_insert_with(|| {
+            // mix the stream into the seed so each stream gets unrelated numbers
+            let stream_seed = (stream as u64 + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+            StdRng::seed_from_u64(seed ^ stream_seed)
+        })
+    }
+}
+
+/// The seed entered on the title screen for the next voyage, or `None` for a random one
+#[derive(Resource, Default, Debug)]
+pub struct VoyageSeed(pub Option<u64>);
+
+fn seed_game_rng(
+    mut rng: ResMut<GameRng>,
+    mut boid_rng: ResMut<BoidRng>,
+    voyage_seed: Res<VoyageSeed>,
+) {
+    let seed = voyage_seed.0.unwrap_or_else(|| rand::thread_rng().gen());
+    info!("Starting a voyage with seed {seed}");
+
+    *rng = GameRng::new(seed);
+    *boid_rng = BoidRng::from_seed(rng.stream(RngStream::Boids).gen());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn streams_are_independent() {
+        let mut busy = GameRng::new(7);
+        let mut quiet = GameRng::new(7);
+
+        for _ in 0..100 {
+            busy.stream(RngStream::Effects).gen::<u32>();
+        }
+
+        for stream in [RngStream::Rain, RngStream::Curiosity, RngStream::Boids] {
+            assert_eq!(
+                busy.stream(stream).gen::<u64>(),
+                quiet.stream(stream).gen::<u64>()
+            );
+        }
+    }
+}
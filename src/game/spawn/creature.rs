@@ -3,18 +3,13 @@
 use bevy::prelude::*;
 use rand::Rng;
 
-use crate::game::assets::{HandleMap, ImageKey};
-
-use super::{
-    encounters::{EncounterType, SpawnEncounter},
-    WindowSize,
-};
+use super::encounters::{EncounterId, EncounterPath};
 
 pub mod adult_whale;
 pub mod baby_whale;
 mod bird;
 pub mod boid;
-mod fish;
+pub mod fish;
 mod iceberg;
 mod ship;
 
@@ -30,71 +25,27 @@ pub(super) fn plugin(app: &mut App) {
         iceberg::plugin,
         boid::plugin,
     ));
-    app.observe(spawn_creature);
 }
 
-#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default)]
-pub struct Creature(pub EncounterType);
-
-fn spawn_creature(
-    trigger: Trigger<SpawnEncounter>,
-    mut commands: Commands,
-    win_size: Res<WindowSize>,
-    image_handles: Res<HandleMap<ImageKey>>,
-    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
-) {
-    let size = win_size.size();
+/// Something the whale can encounter, tagged with the kind of encounter that spawned it
+#[derive(Component, Debug, Clone, PartialEq, Eq)]
+pub struct Creature(pub EncounterId);
 
-    match trigger.event().encounter_type {
-        EncounterType::Bird => {
-            bird::spawn(
-                &mut commands,
-                size,
-                &image_handles,
-                &mut texture_atlas_layouts,
-            );
-        }
-        EncounterType::Fish => {
-            fish::spawn(
-                &mut commands,
-                size,
-                &image_handles,
-                &mut texture_atlas_layouts,
-            );
-        }
-        EncounterType::Ship => {
-            ship::spawn(
-                &mut commands,
-                size,
-                &image_handles,
-                &mut texture_atlas_layouts,
-            );
-        }
-        EncounterType::Iceberg => {
-            iceberg::spawn(
-                &mut commands,
-                size,
-                &image_handles,
-                &mut texture_atlas_layouts,
-            );
-        }
-        EncounterType::AdultWhale => {
-            adult_whale::spawn(
-                &mut commands,
-                size,
-                &image_handles,
-                &mut texture_atlas_layouts,
-            );
-        }
-        EncounterType::BabyWhale => {
-            warn!("Found encounter BabyWhale, this shouldn't be created using SpawnEncounter. Ignoring");
-        }
+/// Returns the ends of the scripted path for a creature if it has one, or a random path otherwise
+fn get_encounter_path(
+    path: Option<EncounterPath>,
+    window_size: Vec2,
+    sprite_size: f32,
+    rng: &mut impl Rng,
+) -> (Vec3, Vec3) {
+    match path {
+        Some(path) => path.resolve(window_size),
+        None => get_creature_path(window_size, sprite_size, rng),
     }
 }
 
 /// Returns the ends of a path for spawning a creature
-fn get_creature_path(window_size: Vec2, sprite_size: f32) -> (Vec3, Vec3) {
-    let mut rng = rand::thread_rng();
+fn get_creature_path(window_size: Vec2, sprite_size: f32, rng: &mut impl Rng) -> (Vec3, Vec3) {
     let half_size = window_size / 2.0;
 
     // check if we're going from L-R or U-D
@@ -1,4 +1,3 @@
-use bevoids::boids::BoidRepulsor;
 use bevy::prelude::*;
 use rand::Rng;
 
@@ -9,41 +8,50 @@ use crate::{
         audio::sfx::PlaySfx,
         flipper::FlipComplete,
         movement::{MoveWithVelocity, RotateToFaceMovement, WHALE_TRAVEL_SPEED},
+        rng::{GameRng, RngStream},
         spawn::{
-            encounters::{EncounterTimers, EncounterType},
+            encounters::{
+                EncounterAppExt, EncounterId, EncounterPath, EncounterSchedule, EncounterTimers,
+            },
             player::Whale,
+            WindowSize,
         },
         weather::{TravelDirection, TravelDistance},
     },
     screen::Screen,
 };
 
-use super::{baby_whale::BabyWhaleStatus, bird::Curious, get_creature_path, Creature};
+use super::{
+    baby_whale::BabyWhaleStatus, bird::Curious, boid::get_whale_predator, get_encounter_path,
+    Creature,
+};
+
+pub const ADULT_WHALE: EncounterId = EncounterId::new("adult_whale");
 
 #[derive(Component)]
 pub struct AdultWhale;
 
 pub(super) fn plugin(app: &mut App) {
+    // adults arrive at a time picked each flip rather than from the encounter table
+    app.add_encounter(ADULT_WHALE, EncounterSchedule::OnDemand, spawn);
     app.observe(set_adult_spawn_time);
     app.add_systems(OnEnter(Screen::Playing), set_initial_adult_spawn);
-    app.add_systems(
-        Update,
-        adult_whale_gain_curiosity.run_if(in_state(Screen::Playing)),
-    );
     app.add_systems(
         FixedUpdate,
-        adult_whale_follows_player_whale.run_if(in_state(Screen::Playing)),
+        (adult_whale_gain_curiosity, adult_whale_follows_player_whale)
+            .run_if(in_state(Screen::Playing)),
     );
 }
 
 fn set_initial_adult_spawn(mut encounter_timers: ResMut<EncounterTimers>) {
     info!("Setting initial adult whale spawn time");
-    encounter_timers.set_adult_spawn(37.5);
+    encounter_timers.spawn_at(ADULT_WHALE, 37.5);
 }
 
 fn set_adult_spawn_time(
     _trigger: Trigger<FlipComplete>,
     distance: Res<TravelDistance>,
+    mut rng: ResMut<GameRng>,
     mut encounter_timers: ResMut<EncounterTimers>,
 ) {
     if !matches!(distance.travel_direction(), TravelDirection::North) {
@@ -52,12 +60,12 @@ fn set_adult_spawn_time(
     }
 
     info!("Considering whale spawn");
-    let mut rng = rand::thread_rng();
+    let rng = rng.stream(RngStream::Encounters);
 
     if distance.get_flip_number() == 1 || rng.gen_bool(0.5) {
         let time = rng.gen_range(23.0..39.0);
         info!("Planning an adult whale arrival at {time}");
-        encounter_timers.set_adult_spawn(time);
+        encounter_timers.spawn_at(ADULT_WHALE, time);
     }
 }
 
@@ -67,6 +75,7 @@ pub const WHALE_CURIOSITY_DISTANCE: f32 = 80.;
 fn adult_whale_gain_curiosity(
     mut commands: Commands,
     mut baby_status: ResMut<BabyWhaleStatus>,
+    mut rng: ResMut<GameRng>,
     whales: Query<&Transform, With<Whale>>,
     adults: Query<(Entity, &Transform), (With<AdultWhale>, Without<Curious>)>,
 ) {
@@ -75,7 +84,7 @@ fn adult_whale_gain_curiosity(
     }
 
     let whale = whales.single();
-    let mut rng = rand::thread_rng();
+    let rng = rng.stream(RngStream::Curiosity);
     let target = Vec3::new(
         whale.translation.x + rng.gen_range(-20.0..20.0),
         whale.translation.y + rng.gen_range(-20.0..20.0),
@@ -123,30 +132,31 @@ fn adult_whale_follows_player_whale(
     }
 }
 
-/// Spawns an adult whale when `SpawnEncounter(Fish)` is triggered. Called by the parent creature plugin.
-pub(super) fn spawn(
-    commands: &mut Commands,
-    win_size: Vec2,
-    image_handles: &HandleMap<ImageKey>,
-    texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
+/// Spawns an adult whale when the adult whale encounter is triggered
+fn spawn(
+    In(path): In<Option<EncounterPath>>,
+    mut commands: Commands,
+    win_size: Res<WindowSize>,
+    image_handles: Res<HandleMap<ImageKey>>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut rng: ResMut<GameRng>,
 ) {
+    let win_size = win_size.size();
+    let rng = rng.stream(RngStream::Creatures);
     let layout = TextureAtlasLayout::from_grid(UVec2::splat(64), 8, 8, None, None);
     let texture_atlas_layout = texture_atlas_layouts.add(layout);
 
     let mut player_animation = SpriteAnimationPlayer::new();
     player_animation.set_frame_interval(SLOW_WHALE_FRAME_MILLIS);
 
-    let (from_pos, to_pos) = get_creature_path(win_size, 64.);
+    let (from_pos, to_pos) = get_encounter_path(path, win_size, 64., rng);
 
     // now spawn the baby
     commands.spawn((
         Name::new("Adult Whale"),
-        Creature(EncounterType::AdultWhale),
+        Creature(ADULT_WHALE),
         AdultWhale,
-        BoidRepulsor {
-            strength: 0.7,
-            range: 80.,
-        },
+        get_whale_predator(),
         SpriteBundle {
             texture: image_handles[&ImageKey::Creatures].clone_weak(),
             transform: Transform::from_translation(from_pos),
@@ -10,7 +10,8 @@ use crate::{
         movement::{
             MoveTowardsLocation, MoveWithVelocity, RotateToFaceMovement, WHALE_TRAVEL_SPEED,
         },
-        spawn::{encounters::EncounterType, player::Whale, WindowSize},
+        rng::{GameRng, RngStream, NEW_VOYAGE},
+        spawn::{encounters::EncounterId, player::Whale, WindowSize},
         weather::TravelDistance,
     },
     screen::Screen,
@@ -18,6 +19,9 @@ use crate::{
 
 use super::Creature;
 
+/// Babies are spawned when the whale flips rather than as an encounter, so this isn't registered
+pub const BABY_WHALE: EncounterId = EncounterId::new("baby_whale");
+
 #[derive(Resource, Default)]
 pub struct BabyWhaleStatus {
     pub departure_time: f32,
@@ -31,7 +35,8 @@ const BABY_WHALE_SPAWN_DISTANCE: f32 = 20.;
 
 pub(super) fn plugin(app: &mut App) {
     app.init_resource::<BabyWhaleStatus>()
-        .observe(spawn_baby_on_flip);
+        .observe(spawn_baby_on_flip)
+        .add_systems(NEW_VOYAGE, reset_baby_whale_status);
 
     app.add_systems(
         FixedUpdate,
@@ -40,17 +45,22 @@ pub(super) fn plugin(app: &mut App) {
     app.add_systems(Update, depart_baby_whale.run_if(in_state(Screen::Playing)));
 }
 
+fn reset_baby_whale_status(mut baby: ResMut<BabyWhaleStatus>) {
+    *baby = BabyWhaleStatus::default();
+}
+
 fn spawn_baby_on_flip(
     _trigger: Trigger<FlipComplete>,
     mut commands: Commands,
     mut baby: ResMut<BabyWhaleStatus>,
+    mut rng: ResMut<GameRng>,
     image_handles: Res<HandleMap<ImageKey>>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
     whales: Query<&Transform, With<Whale>>,
 ) {
     // if we have a baby calculate its departure time and prevent spawning a baby on the next flip
     if baby.has_whale {
-        baby.departure_time = rand::thread_rng().gen_range(35.0..55.0);
+        baby.departure_time = rng.stream(RngStream::Creatures).gen_range(35.0..55.0);
         baby.has_whale = false; // no longer have a whale
     } else {
         baby.departure_time = 0.;
@@ -67,7 +77,7 @@ fn spawn_baby_on_flip(
     let entity = commands
         .spawn((
             Name::new("Baby Whale"),
-            Creature(EncounterType::BabyWhale),
+            Creature(BABY_WHALE),
             BabyWhale,
             SpriteBundle {
                 texture: image_handles[&ImageKey::Creatures].clone_weak(),
@@ -115,12 +125,13 @@ fn depart_baby_whale(
     win_size: Res<WindowSize>,
     distance: Res<TravelDistance>,
     baby_stats: Res<BabyWhaleStatus>,
+    mut rng: ResMut<GameRng>,
     babies: Query<Entity, With<BabyWhale>>,
 ) {
     if let Ok(baby) = babies.get_single() {
         if baby_stats.departure_time < distance.get() {
             // get a location outside the screen
-            let target = win_size.get_random_position_outside();
+            let target = win_size.get_random_position_outside(rng.stream(RngStream::Creatures));
 
             // the baby can leave
             commands
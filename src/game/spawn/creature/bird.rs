@@ -8,12 +8,19 @@ use crate::{
         audio::sfx::PlaySfx,
         flipper::Flippable,
         movement::{MoveTowardsLocation, MoveWithVelocity, WHALE_TRAVEL_SPEED},
-        spawn::{encounters::EncounterType, player::Whale, WindowSize},
+        rng::{GameRng, RngStream},
+        spawn::{
+            encounters::{EncounterAppExt, EncounterId, EncounterPath, EncounterSchedule},
+            player::Whale,
+            WindowSize,
+        },
     },
     screen::Screen,
 };
 
-use super::{get_creature_path, Creature};
+use super::{get_creature_path, get_encounter_path, Creature};
+
+pub const BIRD: EncounterId = EncounterId::new("bird");
 
 pub const BIRD_SPEED: f32 = WHALE_TRAVEL_SPEED * 1.2;
 
@@ -37,13 +44,13 @@ pub struct Bird;
 pub struct LosingCuriosity;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(
-        Update,
-        (gain_curiosity, lose_curiosity).run_if(in_state(Screen::Playing)),
-    );
+    app.add_encounter(BIRD, EncounterSchedule::Table, spawn);
+
     app.add_systems(
         FixedUpdate,
         (
+            gain_curiosity,
+            lose_curiosity,
             scale_curious_birds,
             curious_birds_follow_whale,
             return_to_flying_off,
@@ -52,24 +59,27 @@ pub(super) fn plugin(app: &mut App) {
     );
 }
 
-/// Spawns a bird when `SpawnEncounter(Bird)` is triggered. Called by the parent creature plugin
-pub(super) fn spawn(
-    commands: &mut Commands,
-    win_size: Vec2,
-    image_handles: &HandleMap<ImageKey>,
-    texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
+/// Spawns a bird when the bird encounter is triggered
+fn spawn(
+    In(path): In<Option<EncounterPath>>,
+    mut commands: Commands,
+    win_size: Res<WindowSize>,
+    image_handles: Res<HandleMap<ImageKey>>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut rng: ResMut<GameRng>,
 ) {
+    let win_size = win_size.size();
+    let rng = rng.stream(RngStream::Creatures);
     let layout = TextureAtlasLayout::from_grid(UVec2::splat(64), 8, 8, None, None);
     let texture_atlas_layout = texture_atlas_layouts.add(layout);
 
     let player_animation = SpriteAnimationPlayer::bird();
 
-    let (from_pos, to_pos) = get_creature_path(win_size, 64.);
-    let mut rng = rand::thread_rng();
+    let (from_pos, to_pos) = get_encounter_path(path, win_size, 64., rng);
 
     let mut entity_cmds = commands.spawn((
         Name::new("Bird"),
-        Creature(EncounterType::Bird),
+        Creature(BIRD),
         Bird,
         SpriteBundle {
             texture: image_handles[&ImageKey::Creatures].clone_weak(),
@@ -98,6 +108,7 @@ pub const BIRD_CURIOSITY_THRESHOLD: f32 = 120.;
 fn gain_curiosity(
     mut commands: Commands,
     time: Res<Time>,
+    mut rng: ResMut<GameRng>,
     whales: Query<&Transform, With<Whale>>,
     birds: Query<(Entity, &Transform), (With<Bird>, Without<Curious>, Without<Incurious>)>,
 ) {
@@ -106,7 +117,7 @@ fn gain_curiosity(
     }
 
     let whale = whales.single();
-    let mut rng = rand::thread_rng();
+    let rng = rng.stream(RngStream::Curiosity);
     let target = Vec3::new(
         whale.translation.x + rng.gen_range(-20.0..20.0),
         whale.translation.y + rng.gen_range(-20.0..20.0),
@@ -132,6 +143,7 @@ fn gain_curiosity(
 }
 
 fn curious_birds_follow_whale(
+    mut rng: ResMut<GameRng>,
     whales: Query<&Transform, With<Whale>>,
     mut birds: Query<
         (&Transform, &mut MoveWithVelocity),
@@ -143,7 +155,7 @@ fn curious_birds_follow_whale(
     }
 
     let whale = whales.single();
-    let mut rng = rand::thread_rng();
+    let rng = rng.stream(RngStream::Curiosity);
 
     for (bird_tx, mut bird) in &mut birds {
         bird.0 = (Vec3::new(
@@ -169,13 +181,15 @@ fn lose_curiosity(
     mut commands: Commands,
     win_size: Res<WindowSize>,
     time: Res<Time>,
+    mut rng: ResMut<GameRng>,
     birds: Query<(Entity, &Curious), (With<Bird>, Without<LosingCuriosity>)>,
 ) {
     let size = win_size.size();
+    let rng = rng.stream(RngStream::Curiosity);
     for (bird, curious) in &birds {
         if curious.until <= time.elapsed_seconds() {
             // update the entity so it navigates back off the screen
-            let (_, target) = get_creature_path(size, 64.);
+            let (_, target) = get_creature_path(size, 64., rng);
 
             info!("bird {bird:?} losing curiosity");
             commands
@@ -7,39 +7,108 @@
 // - separate gravity into a separate per entity component
 // - make gravity a vec3 instead of assuming its always down
 
-use bevoids::boids::{
-    Boid, BoidSpace, BoidSpeed, BoidTurningStrength, BoidViewConfig, BoidsConfig, BoidsPlugin,
+use bevoids::{
+    boids::{BoidCollisionGroup, BoidLod, BoidPredator, BoidPrey, BoidSpace, BoidsPlugin, Flock},
+    preset::BoidPreset,
 };
 use bevy::prelude::*;
 
-const BOID_MIN_SPEED: f32 = 20.0;
-const BOID_MAX_SPEED: f32 = 30.0;
-
-const BOID_COHESION: f32 = 0.25;
-const BOID_SEPARATION: f32 = 0.5;
-const BOID_ALIGNMENT: f32 = 0.08;
-const BOID_BORDER_TURN_STRENGTH: f32 = 200.0;
-
-const BOID_FOV: u32 = 240;
-const BOID_VIEW_RANGE: f32 = 120.0;
-const BOID_PROTECTED_RANGE: f32 = 15.0;
-
-pub fn get_default_boid() -> Boid {
-    Boid::new(
-        BoidSpeed::new(BOID_MIN_SPEED, BOID_MAX_SPEED),
-        BoidTurningStrength::new(
-            BOID_COHESION,
-            BOID_SEPARATION,
-            BOID_ALIGNMENT,
-            BOID_BORDER_TURN_STRENGTH,
-        ),
-        BoidViewConfig::new(BOID_FOV, BOID_PROTECTED_RANGE, BOID_VIEW_RANGE),
-    )
+use crate::{
+    game::{
+        assets::{BoidPresetKey, HandleMap},
+        movement::WINDOW_DESPAWN_BUFFER,
+        spawn::WindowSize,
+    },
+    screen::Screen,
+};
+
+const PREY_ALERT_RANGE: f32 = 90.0;
+const PREY_FLEE_STRENGTH: f32 = 1.2;
+const PREY_FLEE_SPEED_BOOST: f32 = 1.8;
+const PREY_BAIT_BALL_COHESION: f32 = 2.5;
+const PREY_SCATTER_STRENGTH: f32 = 3.0;
+
+/// How fast a whale has to swim at a school of fish before it scatters
+const WHALE_CHARGE_SPEED: f32 = 15.0;
+
+/// How many ticks fish just off screen wait between steering updates
+const FISH_OFF_SCREEN_STEER_INTERVAL: u32 = 4;
+
+/// The collision groups fish schools are spawned into
+pub const FISH_SCHOOL_GROUPS: [BoidCollisionGroup; 4] = [
+    BoidCollisionGroup::GROUP_1,
+    BoidCollisionGroup::GROUP_2,
+    BoidCollisionGroup::GROUP_3,
+    BoidCollisionGroup::GROUP_4,
+];
+
+pub fn get_fish_prey() -> BoidPrey {
+    BoidPrey {
+        alert_range: PREY_ALERT_RANGE,
+        flee_strength: PREY_FLEE_STRENGTH,
+        flee_speed_boost: PREY_FLEE_SPEED_BOOST,
+        bait_ball_cohesion: PREY_BAIT_BALL_COHESION,
+        scatter_strength: PREY_SCATTER_STRENGTH,
+    }
+}
+
+pub fn get_whale_predator() -> BoidPredator {
+    BoidPredator::new(WHALE_CHARGE_SPEED)
 }
 
+/// Marks the [Flock] that all fish schools belong to
+#[derive(Component)]
+pub struct FishFlock;
+
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins(BoidsPlugin).insert_resource(BoidsConfig {
-        space: BoidSpace::TwoDimensional,
-        debug: false,
+    app.add_plugins(BoidsPlugin);
+
+    // the preset asset type is registered by the boids plugin, so load presets after adding it
+    app.register_type::<HandleMap<BoidPresetKey>>();
+    app.init_resource::<HandleMap<BoidPresetKey>>();
+
+    app.add_systems(OnExit(Screen::Loading), spawn_fish_flock);
+    app.add_systems(Update, update_fish_lod);
+}
+
+/// Fish on screen are steered every tick, fish in the buffer around the screen less often, and
+/// any further out just coast along until they're despawned
+fn update_fish_lod(win_size: Res<WindowSize>, mut flocks: Query<&mut Flock, With<FishFlock>>) {
+    let on_screen = win_size.half().length();
+
+    for mut flock in &mut flocks {
+        flock.lod = Some(BoidLod::new(
+            on_screen,
+            on_screen + WINDOW_DESPAWN_BUFFER,
+            FISH_OFF_SCREEN_STEER_INTERVAL,
+        ));
+    }
+}
+
+/// Spawns the fish flock once the fish preset has loaded. Fish tuning lives in
+/// `assets/boids/fish.boid.ron`, and is picked up live when that file changes. If the preset
+/// failed to load, the fish make do with default tuning until the file is fixed.
+fn spawn_fish_flock(
+    mut commands: Commands,
+    preset_handles: Res<HandleMap<BoidPresetKey>>,
+    presets: Res<Assets<BoidPreset>>,
+) {
+    let handle = &preset_handles[&BoidPresetKey::Fish];
+    let preset = presets.get(handle).cloned().unwrap_or_else(|| {
+        error!("Fish boid preset isn't loaded, spawning the fish flock with default tuning");
+        BoidPreset::default()
     });
+
+    commands.spawn((
+        Name::new("Fish Flock"),
+        FishFlock,
+        Flock::new(
+            BoidSpace::TwoDimensional,
+            preset.speed,
+            preset.turning_strength,
+            preset.view_config,
+        )
+        .with_agility(preset.agility),
+        handle.clone_weak(),
+    ));
 }
@@ -1,4 +1,7 @@
-use bevoids::boids::{BoidCollisionGroup, BoidJitter};
+use bevoids::{
+    boids::{BoidCollisionGroup, BoidFlock, BoidGoal, BoidSchool, Flock},
+    leader::BoidLeader,
+};
 use bevy::prelude::*;
 use rand::{seq::SliceRandom, Rng};
 
@@ -6,61 +9,91 @@ use crate::{
     game::{
         animation::SpriteAnimationPlayer,
         assets::{HandleMap, ImageKey},
-        spawn::encounters::EncounterType,
+        movement::{DespawnWhenOutOfWindow, WINDOW_DESPAWN_BUFFER},
+        rng::{GameRng, RngStream},
+        spawn::{
+            encounters::{EncounterAppExt, EncounterId, EncounterPath, EncounterSchedule},
+            WindowSize,
+        },
     },
     screen::Screen,
 };
 
-use super::{boid::get_default_boid, get_creature_path, Creature};
+use super::{
+    boid::{get_fish_prey, FishFlock, FISH_SCHOOL_GROUPS},
+    get_encounter_path, Creature,
+};
+
+pub const FISH: EncounterId = EncounterId::new("fish");
 
 /// Marker component for fish
 #[derive(Component)]
 pub struct Fish;
 
-/// The "lead fish" in the group, used for e.g. to see how far away fish are from the whale
-#[derive(Component)]
-pub struct LeadFish;
+/// How strongly fish steer towards the far side of the screen
+const FISH_GOAL_STRENGTH: f32 = 0.5;
 
-pub(super) fn plugin(_app: &mut App) {
-    // nothing for now, as fish have their AI covered by Boids
+/// How many ordinary fish the lead fish counts as when the school follows it
+const LEAD_FISH_WEIGHT: f32 = 4.0;
+
+pub(super) fn plugin(app: &mut App) {
+    // fish have their AI covered by Boids, this just keeps track of where they are
+    app.add_encounter(FISH, EncounterSchedule::Table, spawn);
 }
 
-/// Spawns a fish when `SpawnEncounter(Fish)` is triggered. Called by the parent creature plugin
-pub(super) fn spawn(
-    commands: &mut Commands,
-    win_size: Vec2,
-    image_handles: &HandleMap<ImageKey>,
-    texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
+/// Spawns a school of fish when the fish encounter is triggered
+fn spawn(
+    In(path): In<Option<EncounterPath>>,
+    mut commands: Commands,
+    win_size: Res<WindowSize>,
+    image_handles: Res<HandleMap<ImageKey>>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut rng: ResMut<GameRng>,
+    mut next_school: Local<u32>,
+    fish_flocks: Query<(Entity, &Flock), With<FishFlock>>,
 ) {
+    let Ok((flock_entity, flock)) = fish_flocks.get_single() else {
+        warn!("No fish flock found, skipping fish spawn");
+        return;
+    };
+
+    let win_size = win_size.size();
     let layout = TextureAtlasLayout::from_grid(UVec2::splat(16), 8, 1, None, None);
     let texture_atlas_layout = texture_atlas_layouts.add(layout);
 
-    let mut rng = rand::thread_rng();
+    let rng = rng.stream(RngStream::Creatures);
 
     let mut player_animation = SpriteAnimationPlayer::fish();
     player_animation.set_frame(rng.gen_range(0..8));
 
-    let (from_pos, to_pos) = get_creature_path(win_size, 64.);
+    let (from_pos, to_pos) = get_encounter_path(path, win_size, 64., rng);
 
     // avoid some collisions between schools, but occasionally let them interact
-    let collision = [
-        BoidCollisionGroup::GROUP_1,
-        BoidCollisionGroup::GROUP_2,
-        BoidCollisionGroup::GROUP_3,
-        BoidCollisionGroup::GROUP_4,
-    ]
-    .choose(&mut rng)
-    .unwrap_or(&BoidCollisionGroup::GROUP_18);
+    let collision = FISH_SCHOOL_GROUPS
+        .choose(rng)
+        .unwrap_or(&BoidCollisionGroup::GROUP_18);
 
     let school_size = rng.gen_range(5..35);
 
+    // every school shares the fish flock, so tell them apart for leaders and analytics
+    let school = BoidSchool(*next_school);
+    *next_school = next_school.wrapping_add(1);
+
+    // aim past the despawn buffer so the school actually leaves the screen
+    let direction = (to_pos - from_pos).normalize();
+    let goal = BoidGoal::new(
+        vec![to_pos + direction * 2. * WINDOW_DESPAWN_BUFFER],
+        FISH_GOAL_STRENGTH,
+        WINDOW_DESPAWN_BUFFER,
+    );
+
     for fish in 0..school_size {
-        let mut boid = get_default_boid();
-        boid.set_velocity((to_pos - from_pos).normalize() * 100.);
+        let mut boid = flock.boid();
+        boid.set_velocity(direction * 100.);
 
         let mut entity_cmds = commands.spawn((
             Name::new(format!("Fish {fish}")),
-            Creature(EncounterType::Fish),
+            Creature(FISH),
             Fish,
             SpriteBundle {
                 texture: image_handles[&ImageKey::Fish].clone_weak(),
@@ -77,12 +110,17 @@ pub(super) fn spawn(
             player_animation.clone(),
             StateScoped(Screen::Playing),
             boid,
-            BoidJitter(1.3),
+            BoidFlock(flock_entity),
+            school,
+            get_fish_prey(),
             *collision,
+            goal.clone(),
+            DespawnWhenOutOfWindow,
         ));
 
+        // the rest of the school follows the lead fish, and a new one takes over if it leaves
         if fish == 0 {
-            entity_cmds.insert(LeadFish);
+            entity_cmds.insert(BoidLeader::new(LEAD_FISH_WEIGHT));
         }
     }
 }
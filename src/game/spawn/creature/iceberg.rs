@@ -1,4 +1,4 @@
-use bevoids::boids::BoidRepulsor;
+use bevoids::obstacle::{BoidObstacle, BoidObstacleShape};
 use bevy::prelude::*;
 use rand::Rng;
 
@@ -6,8 +6,12 @@ use crate::{
     game::{
         assets::{HandleMap, ImageKey},
         flipper::Flippable,
-        movement::{MoveWithVelocity, RotateToFaceMovement, WHALE_TRAVEL_SPEED},
-        spawn::encounters::EncounterType,
+        movement::{DriftWithCurrent, MoveWithVelocity, RotateToFaceMovement, WHALE_TRAVEL_SPEED},
+        rng::{GameRng, RngStream},
+        spawn::{
+            encounters::{EncounterAppExt, EncounterId, EncounterPath, EncounterSchedule},
+            WindowSize,
+        },
         weather::TintWithDayNightCycle,
     },
     screen::Screen,
@@ -15,41 +19,57 @@ use crate::{
 
 use super::Creature;
 
+pub const ICEBERG: EncounterId = EncounterId::new("iceberg");
+
 pub const SHIP_SPEED: f32 = WHALE_TRAVEL_SPEED * 0.8;
 
 /// Denotes a ship
 #[derive(Component)]
 pub struct Iceberg;
 
-pub(super) fn plugin(_app: &mut App) {
-    // actually nothing :shrug:
+pub(super) fn plugin(app: &mut App) {
+    app.add_encounter(ICEBERG, EncounterSchedule::Table, spawn);
 }
 
-/// Spawns an iceberg when `SpawnEncounter(Iceberg)` is triggered. Called by the parent creature plugin
-pub(super) fn spawn(
-    commands: &mut Commands,
-    win_size: Vec2,
-    image_handles: &HandleMap<ImageKey>,
-    texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
+/// Spawns an iceberg when the iceberg encounter is triggered
+fn spawn(
+    In(path): In<Option<EncounterPath>>,
+    mut commands: Commands,
+    win_size: Res<WindowSize>,
+    image_handles: Res<HandleMap<ImageKey>>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut rng: ResMut<GameRng>,
 ) {
+    let win_size = win_size.size();
+    let rng = rng.stream(RngStream::Creatures);
     let layout = TextureAtlasLayout::from_grid(UVec2::new(32, 32), 9, 2, None, None);
     let texture_atlas_layout = texture_atlas_layouts.add(layout);
 
     let half_x = win_size.x / 2.;
 
-    let mut rng = rand::thread_rng();
+    // icebergs float up the screen unless they've been given somewhere to go
+    let (from_pos, direction) = match path {
+        Some(path) => {
+            let (from_pos, to_pos) = path.resolve(win_size);
+            (from_pos, (to_pos - from_pos).normalize_or(Vec3::Y))
+        }
+        None => (
+            Vec3::new(
+                rng.gen_range((-half_x + 32.)..(half_x - 32.)),
+                -win_size.y / 2. - 48.0,
+                0.,
+            ),
+            Vec3::Y,
+        ),
+    };
 
     commands.spawn((
         Name::new("Iceberg"),
-        Creature(EncounterType::Iceberg),
+        Creature(ICEBERG),
         Iceberg,
         SpriteBundle {
             texture: image_handles[&ImageKey::Features].clone_weak(),
-            transform: Transform::from_translation(Vec3::new(
-                rng.gen_range((-half_x + 32.)..(half_x - 32.)),
-                -win_size.y / 2. - 48.0,
-                1.5,
-            )), // move it up slightly so it obscures waves, the whale, fish, etc
+            transform: Transform::from_translation(from_pos + Vec3::Z * 1.5), // move it up slightly so it obscures waves, the whale, fish, etc
             ..Default::default()
         },
         TextureAtlas {
@@ -57,13 +77,14 @@ pub(super) fn spawn(
             index: rng.gen_range(8..14),
         },
         Flippable,
-        BoidRepulsor {
-            strength: 0.6,
-            range: 45.,
+        BoidObstacle {
+            margin: 8.,
+            ..BoidObstacle::new(BoidObstacleShape::Circle { radius: 16. }, 1.2, 1.5)
         },
         TintWithDayNightCycle,
         StateScoped(Screen::Playing),
         RotateToFaceMovement,
-        MoveWithVelocity(Vec3::Y * 0.75 * SHIP_SPEED),
+        MoveWithVelocity(direction * 0.75 * SHIP_SPEED),
+        DriftWithCurrent,
     ));
 }
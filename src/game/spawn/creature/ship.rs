@@ -1,4 +1,7 @@
-use bevoids::boids::BoidRepulsor;
+use bevoids::{
+    boids::{BoidFalloff, BoidRepulsor},
+    obstacle::{BoidObstacle, BoidObstacleShape},
+};
 use bevy::prelude::*;
 
 use crate::{
@@ -7,42 +10,58 @@ use crate::{
         assets::{HandleMap, ImageKey, SfxKey},
         audio::sfx::PlaySfx,
         movement::{MoveWithVelocity, RotateToFaceMovement, WHALE_TRAVEL_SPEED},
-        spawn::encounters::EncounterType,
+        rng::{GameRng, RngStream},
+        spawn::{
+            encounters::{EncounterAppExt, EncounterId, EncounterPath, EncounterSchedule},
+            WindowSize,
+        },
         weather::TintWithDayNightCycle,
     },
     screen::Screen,
 };
 
-use super::{get_creature_path, Creature};
+use super::{get_encounter_path, Creature};
+
+pub const SHIP: EncounterId = EncounterId::new("ship");
 
 pub const SHIP_SPEED: f32 = WHALE_TRAVEL_SPEED * 0.8;
 
+/// How far ahead of its bow a ship scatters fish
+const SHIP_BOW_WAVE_RANGE: f32 = 110.;
+
+/// How wide the area ahead of the bow that scatters fish is, in radians either side
+const SHIP_BOW_WAVE_HALF_ANGLE: f32 = 0.6;
+
 /// Denotes a ship
 #[derive(Component)]
 pub struct Ship;
 
-pub(super) fn plugin(_app: &mut App) {
-    // actually nothing :shrug:
+pub(super) fn plugin(app: &mut App) {
+    app.add_encounter(SHIP, EncounterSchedule::Table, spawn);
 }
 
-/// Spawns a ship when `SpawnEncounter(Ship)` is triggered. Called by the parent creature plugin
-pub(super) fn spawn(
-    commands: &mut Commands,
-    win_size: Vec2,
-    image_handles: &HandleMap<ImageKey>,
-    texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
+/// Spawns a ship when the ship encounter is triggered
+fn spawn(
+    In(path): In<Option<EncounterPath>>,
+    mut commands: Commands,
+    win_size: Res<WindowSize>,
+    image_handles: Res<HandleMap<ImageKey>>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut rng: ResMut<GameRng>,
 ) {
+    let win_size = win_size.size();
+    let rng = rng.stream(RngStream::Creatures);
     let layout = TextureAtlasLayout::from_grid(UVec2::new(64, 128), 5, 1, None, None);
     let texture_atlas_layout = texture_atlas_layouts.add(layout);
 
     let player_animation = SpriteAnimationPlayer::ship();
 
-    let (from_pos, to_pos) = get_creature_path(win_size, 64.);
+    let (from_pos, to_pos) = get_encounter_path(path, win_size, 64., rng);
 
     let entity = commands
         .spawn((
             Name::new("Ship"),
-            Creature(EncounterType::Ship),
+            Creature(SHIP),
             Ship,
             SpriteBundle {
                 texture: image_handles[&ImageKey::Ships].clone_weak(),
@@ -55,10 +74,23 @@ pub(super) fn spawn(
                 index: player_animation.get_atlas_index(),
             },
             player_animation,
-            BoidRepulsor {
-                strength: 0.5,
-                range: 45.,
+            // the hull, along the direction of travel
+            BoidObstacle {
+                margin: 8.,
+                ..BoidObstacle::new(
+                    BoidObstacleShape::Capsule {
+                        radius: 24.,
+                        half_length: 40.,
+                    },
+                    1.,
+                    1.5,
+                )
             },
+            // scatter fish ahead of the ship. Ships face away from where they're going, so the
+            // bow is down the sprite
+            BoidRepulsor::new(0.8, SHIP_BOW_WAVE_RANGE)
+                .with_falloff(BoidFalloff::Linear)
+                .with_cone(Vec3::NEG_Y, SHIP_BOW_WAVE_HALF_ANGLE),
             StateScoped(Screen::Playing),
             RotateToFaceMovement,
             MoveWithVelocity((to_pos - from_pos).normalize_or_zero() * SHIP_SPEED),
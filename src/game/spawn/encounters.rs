@@ -1,269 +1,549 @@
 //! an encounter system
 
-use bevy::{prelude::*, utils::HashMap};
+use std::{borrow::Cow, fmt};
+
+use bevy::{asset::AssetLoadFailedEvent, ecs::system::SystemId, prelude::*, utils::HashMap};
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
-use crate::{
-    game::weather::{TravelDirection, TravelDistance},
-    screen::Screen,
+use crate::game::{
+    assets::{EncounterTableKey, HandleMap},
+    rng::{GameRng, RngStream, VoyageSet, NEW_VOYAGE},
+    spawn::creature::Creature,
+    weather::TravelDistance,
 };
 
-use std::ops::Range;
+use director::EncounterDirector;
+use table::{EncounterRule, EncounterTable, EncounterTableLoader};
+
+pub mod director;
+pub mod table;
 
+/// Spawns an encounter straight away, using the spawn system it was registered with
 #[derive(Event, Debug)]
 pub struct SpawnEncounter {
-    pub encounter_type: EncounterType,
+    pub encounter: EncounterId,
+    /// Where the encounter should go, or `None` to let it pick its own way across the screen
+    pub path: Option<EncounterPath>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
-pub enum EncounterType {
-    #[default]
-    Bird,
-    Fish,
-    Ship,
-    Iceberg,
-    AdultWhale,
-    BabyWhale,
+impl SpawnEncounter {
+    pub fn new(encounter: EncounterId) -> Self {
+        Self {
+            encounter,
+            path: None,
+        }
+    }
+
+    pub fn with_path(mut self, path: EncounterPath) -> Self {
+        self.path = Some(path);
+        self
+    }
 }
 
-pub(super) fn plugin(app: &mut App) {
-    app.add_systems(Update, spawn_encounters.run_if(in_state(Screen::Playing)));
-    app.init_resource::<EncounterTimers>();
+/// Where an encounter enters and leaves the screen. Points are fractions of half the window size
+/// from the centre, so `(-1.0, 0.0)` is the middle of the left edge and anything past 1 is off
+/// screen.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct EncounterPath {
+    pub from: (f32, f32),
+    pub to: (f32, f32),
 }
 
-pub struct EncounterSpawnRate {
-    slope: f32,
-    intercept: Range<f32>,
+impl EncounterPath {
+    /// Gets the ends of the path in world space
+    pub fn resolve(&self, window_size: Vec2) -> (Vec3, Vec3) {
+        let half = window_size / 2.;
+        (
+            (Vec2::from(self.from) * half).extend(0.),
+            (Vec2::from(self.to) * half).extend(0.),
+        )
+    }
 }
 
-impl EncounterSpawnRate {
-    fn next_spawn(&self, t: f32) -> f32 {
-        let mut rng = rand::thread_rng();
-        let intercept = rng.gen_range(self.intercept.clone());
-        t + ((t * self.slope / 100.) + intercept).clamp(1., 100.)
+/// Names a kind of encounter, in the [EncounterRegistry] and in the encounter table
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct EncounterId(Cow<'static, str>);
+
+impl EncounterId {
+    pub const fn new(id: &'static str) -> Self {
+        Self(Cow::Borrowed(id))
     }
 }
 
-pub type EncounterConfig = HashMap<TravelDirection, EncounterSpawnRate>;
+impl fmt::Display for EncounterId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
 
-#[derive(Resource)]
-pub struct EncounterTimers {
-    bird: f32,
-    fish: f32,
-    ship: f32,
-    iceberg: f32,
-    adult_whale: Option<f32>,
-
-    bird_config: EncounterConfig,
-    fish_config: EncounterConfig,
-    ship_config: EncounterConfig,
-    iceberg_config: EncounterConfig,
+/// How an encounter decides when to spawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncounterSchedule {
+    /// Spawns at the rates given in the encounter table
+    Table,
+    /// Only spawns when asked to, with [SpawnEncounter] or [EncounterTimers::spawn_at]
+    OnDemand,
 }
 
-impl Default for EncounterTimers {
-    fn default() -> Self {
-        Self {
-            bird: 12.,
-            fish: 17.,
-            ship: 45.,
-            iceberg: 1.,
-            adult_whale: None,
-
-            bird_config: vec![
-                (
-                    TravelDirection::South,
-                    EncounterSpawnRate {
-                        slope: 0.,
-                        intercept: 18.0..24.0,
-                    },
-                ),
-                (
-                    TravelDirection::North,
-                    EncounterSpawnRate {
-                        slope: 0.,
-                        intercept: 18.0..22.0,
-                    },
-                ),
-            ]
-            .into_iter()
-            .collect(),
-
-            fish_config: vec![
-                (
-                    TravelDirection::South,
-                    EncounterSpawnRate {
-                        slope: 0.,
-                        intercept: 12.0..22.0,
-                    },
-                ),
-                (
-                    TravelDirection::North,
-                    EncounterSpawnRate {
-                        slope: 0.,
-                        intercept: 12.0..22.0,
-                    },
-                ),
-            ]
-            .into_iter()
-            .collect(),
-
-            ship_config: vec![
-                (
-                    TravelDirection::South,
-                    EncounterSpawnRate {
-                        slope: -24.,
-                        intercept: 40.0..50.0,
-                    },
-                ),
-                (
-                    TravelDirection::North,
-                    EncounterSpawnRate {
-                        slope: -24.,
-                        intercept: 40.0..50.0,
-                    },
-                ),
-            ]
-            .into_iter()
-            .collect(),
-            iceberg_config: vec![
-                (
-                    TravelDirection::South,
-                    EncounterSpawnRate {
-                        slope: -67.,
-                        intercept: 2.0..5.0,
-                    },
-                ),
-                (
-                    TravelDirection::North,
-                    EncounterSpawnRate {
-                        slope: 34.,
-                        intercept: -7.0..-3.0,
-                    },
-                ),
-            ]
-            .into_iter()
-            .collect(),
+struct RegisteredEncounter {
+    schedule: EncounterSchedule,
+    spawn: SystemId<Option<EncounterPath>>,
+}
+
+/// Every kind of encounter the game knows how to spawn, added with
+/// [EncounterAppExt::add_encounter].
+#[derive(Resource, Default)]
+pub struct EncounterRegistry {
+    encounters: HashMap<EncounterId, RegisteredEncounter>,
+}
+
+impl EncounterRegistry {
+    /// Gets how an encounter is scheduled, or `None` if it hasn't been registered
+    pub fn schedule(&self, id: &EncounterId) -> Option<EncounterSchedule> {
+        self.encounters.get(id).map(|encounter| encounter.schedule)
+    }
+
+    /// Gets every encounter that spawns at the rates in the encounter table
+    fn table_driven(&self) -> impl Iterator<Item = &EncounterId> {
+        self.encounters
+            .iter()
+            .filter(|(_, encounter)| encounter.schedule == EncounterSchedule::Table)
+            .map(|(id, _)| id)
+    }
+}
+
+/// Registers encounters on an [App], so each creature can live in its own plugin.
+pub trait EncounterAppExt {
+    /// Adds an encounter, which is spawned by running `spawn` as a one shot system. The system is
+    /// given the path from the [SpawnEncounter], if there is one.
+    ///
+    /// ```rust
+    /// pub const BIRD: EncounterId = EncounterId::new("bird");
+    ///
+    /// fn spawn_bird(In(path): In<Option<EncounterPath>>, mut commands: Commands) {
+    ///     // ...
+    /// }
+    ///
+    /// app.add_encounter(BIRD, EncounterSchedule::Table, spawn_bird);
+    /// ```
+    fn add_encounter<M>(
+        &mut self,
+        id: EncounterId,
+        schedule: EncounterSchedule,
+        spawn: impl IntoSystem<Option<EncounterPath>, (), M> + 'static,
+    ) -> &mut Self;
+}
+
+impl EncounterAppExt for App {
+    fn add_encounter<M>(
+        &mut self,
+        id: EncounterId,
+        schedule: EncounterSchedule,
+        spawn: impl IntoSystem<Option<EncounterPath>, (), M> + 'static,
+    ) -> &mut Self {
+        let spawn = self.world_mut().register_system(spawn);
+        let mut registry = self
+            .world_mut()
+            .get_resource_or_insert_with(EncounterRegistry::default);
+
+        if registry.encounters.contains_key(&id) {
+            panic!("The {id} encounter has already been registered");
         }
+
+        registry
+            .encounters
+            .insert(id, RegisteredEncounter { schedule, spawn });
+
+        self
     }
 }
 
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<EncounterTable>();
+    app.init_asset_loader::<EncounterTableLoader>();
+
+    // the table can only be loaded once its asset type is registered
+    app.register_type::<HandleMap<EncounterTableKey>>();
+    app.init_resource::<HandleMap<EncounterTableKey>>();
+
+    app.init_resource::<EncounterRegistry>();
+    app.observe(spawn_encounter);
+
+    app.add_systems(Update, (check_encounter_table, fall_back_on_failed_table));
+    app.add_systems(
+        FixedUpdate,
+        (reschedule_encounters, spawn_encounters)
+            .chain()
+            .in_set(VoyageSet::Encounters),
+    );
+    app.init_resource::<EncounterTimers>();
+
+    app.init_resource::<EncounterDirector>();
+    app.add_systems(NEW_VOYAGE, reset_encounters);
+}
+
+fn reset_encounters(
+    mut encounters: ResMut<EncounterTimers>,
+    mut director: ResMut<EncounterDirector>,
+) {
+    encounters.reset();
+    *director = EncounterDirector::default();
+}
+
+/// When the next of each encounter is due, as a distance since the last flip
+#[derive(Debug, Clone, Copy)]
+struct ScheduledEncounter {
+    next: f32,
+    spawned: u32,
+}
+
+/// Keeps track of when encounters spawn on this leg of the migration.
+///
+/// Encounters are scheduled from the [EncounterTable] the first time they're checked after a
+/// flip, so the table is only read once it's loaded.
+#[derive(Resource, Default)]
+pub struct EncounterTimers {
+    scheduled: HashMap<EncounterId, ScheduledEncounter>,
+    one_off: HashMap<EncounterId, f32>,
+}
+
 impl EncounterTimers {
-    pub fn reset(&mut self, direction: TravelDirection) {
+    /// Starts the schedule over, ready for a new leg of the migration
+    pub fn reset(&mut self) {
         *self = Self::default();
+    }
+
+    /// Sets a time for a single encounter to be spawned on this leg of the migration, on top of
+    /// any from the encounter table
+    pub fn spawn_at(&mut self, encounter: EncounterId, time: f32) {
+        self.one_off.insert(encounter, time);
+    }
+}
+
+/// Works out when an encounter should next spawn, given it last spawned (or was due) at `now`
+fn schedule(rule: &EncounterRule, now: f32, spawned: u32, rng: &mut impl Rng) -> f32 {
+    let capped = rule.max_spawns.is_some_and(|max| spawned >= max);
+    let closed = rule.active.as_ref().is_some_and(|active| now >= active.end);
+    if capped || closed {
+        return f32::MAX;
+    }
+
+    let next = rule.spawn_curve.next_spawn(now, rng);
+
+    // wait for the window to open rather than spawning straight away
+    match &rule.active {
+        Some(active) if next < active.start => active.start,
+        _ => next,
+    }
+}
+
+fn spawn_encounter(
+    trigger: Trigger<SpawnEncounter>,
+    mut commands: Commands,
+    registry: Res<EncounterRegistry>,
+    distance: Res<TravelDistance>,
+    handles: Res<HandleMap<EncounterTableKey>>,
+    tables: Res<Assets<EncounterTable>>,
+    mut director: ResMut<EncounterDirector>,
+) {
+    let SpawnEncounter {
+        encounter: id,
+        path,
+    } = trigger.event();
+    match registry.encounters.get(id) {
+        Some(encounter) => {
+            commands.run_system_with_input(encounter.spawn, *path);
+            let quiet = tables
+                .get(&handles[&EncounterTableKey::Migration])
+                .and_then(|table| table.budget().quiet.as_ref());
+            director.record(id, quiet, distance.get());
+        }
+        None => warn!("No encounter called {id} has been registered, ignoring"),
+    }
+}
 
-        self.iceberg = match direction {
-            TravelDirection::North => 1.0,
-            TravelDirection::South => 65.0,
+/// Warns about encounters in the table that the game doesn't know how to spawn from it
+fn check_encounter_table(
+    mut events: EventReader<AssetEvent<EncounterTable>>,
+    tables: Res<Assets<EncounterTable>>,
+    registry: Res<EncounterRegistry>,
+) {
+    for event in events.read() {
+        let (AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }) = event
+        else {
+            continue;
         };
+        let Some(table) = tables.get(*id) else {
+            continue;
+        };
+
+        for encounter in table.encounters() {
+            match registry.schedule(encounter) {
+                Some(EncounterSchedule::Table) => {}
+                Some(EncounterSchedule::OnDemand) => warn!(
+                    "The {encounter} encounter only spawns on demand, ignoring it in the encounter table"
+                ),
+                None => warn!(
+                    "No encounter called {encounter} has been registered, ignoring it in the encounter table"
+                ),
+            }
+        }
+    }
+}
 
-        info!(
-            "Travelling {direction:?}, first iceberg spawn at {}",
-            self.iceberg
+/// Falls back to the table the game was built with if the one on disk can't be loaded, so
+/// there's still something to encounter. A broken edit while hot reloading just keeps the table
+/// that was already loaded.
+fn fall_back_on_failed_table(
+    mut failed: EventReader<AssetLoadFailedEvent<EncounterTable>>,
+    mut tables: ResMut<Assets<EncounterTable>>,
+) {
+    for event in failed.read() {
+        if tables.contains(event.id) {
+            continue;
+        }
+
+        error!(
+            "Couldn't load the encounter table from {}, using the one the game was built with: {}",
+            event.path, event.error
         );
+        tables.insert(event.id, EncounterTable::shipped());
     }
+}
 
-    /// Sets a time for an adult male to be spawned
-    pub fn set_adult_spawn(&mut self, time: f32) {
-        self.adult_whale = Some(time);
+/// Re-rolls pending spawns when the encounter table is edited, so balancing changes show up
+/// without waiting for the old schedule to run out
+fn reschedule_encounters(
+    mut events: EventReader<AssetEvent<EncounterTable>>,
+    handles: Res<HandleMap<EncounterTableKey>>,
+    tables: Res<Assets<EncounterTable>>,
+    distance: Res<TravelDistance>,
+    mut rng: ResMut<GameRng>,
+    mut encounters: ResMut<EncounterTimers>,
+) {
+    let handle = &handles[&EncounterTableKey::Migration];
+    let modified = events.read().any(|event| event.is_modified(handle.id()));
+    let Some(table) = tables.get(handle).filter(|_| modified) else {
+        return;
+    };
+
+    let now = distance.get();
+    let direction = distance.travel_direction();
+    let rng = rng.stream(RngStream::Encounters);
+
+    for (encounter, scheduled) in encounters.scheduled.iter_mut() {
+        scheduled.next = match table.rule(encounter, direction) {
+            Some(rule) if scheduled.spawned == 0 => rule.first_spawn.max(now),
+            Some(rule) => schedule(rule, now, scheduled.spawned, rng),
+            None => f32::MAX,
+        };
     }
+
+    info!("Encounter table changed, rescheduled encounters");
 }
 
 fn spawn_encounters(
     mut commands: Commands,
     distance: Res<TravelDistance>,
+    handles: Res<HandleMap<EncounterTableKey>>,
+    tables: Res<Assets<EncounterTable>>,
+    registry: Res<EncounterRegistry>,
+    creatures: Query<&Creature>,
+    mut rng: ResMut<GameRng>,
+    mut director: ResMut<EncounterDirector>,
     mut encounters: ResMut<EncounterTimers>,
 ) {
     let now = distance.get();
     let direction = distance.travel_direction();
+    let rng = rng.stream(RngStream::Encounters);
+
+    if let Some(table) = tables.get(&handles[&EncounterTableKey::Migration]) {
+        let budget = table.budget();
+        director.update_live(creatures.iter(), budget);
+
+        for encounter in registry.table_driven() {
+            if let Some(rule) = table.rule(encounter, direction) {
+                encounters
+                    .scheduled
+                    .entry(encounter.clone())
+                    .or_insert_with(|| {
+                        info!(
+                            "Travelling {direction:?}, first {encounter} spawn at {:.02}",
+                            rule.first_spawn
+                        );
+
+                        ScheduledEncounter {
+                            next: rule.first_spawn,
+                            spawned: 0,
+                        }
+                    });
+            }
+        }
 
-    if encounters.bird < now {
-        commands.trigger(SpawnEncounter {
-            encounter_type: EncounterType::Bird,
-        });
+        // the most overdue encounters get first go, so waiting ones aren't starved by the rest
+        let mut due: Vec<_> = encounters
+            .scheduled
+            .iter()
+            .filter(|(_, scheduled)| scheduled.next < now)
+            .map(|(encounter, scheduled)| (encounter.clone(), scheduled.next))
+            .collect();
+        due.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+
+        for (encounter, _) in due {
+            let Some(rule) = table.rule(&encounter, direction) else {
+                continue;
+            };
+
+            let active = rule
+                .active
+                .as_ref()
+                .is_none_or(|active| active.contains(&now));
+            if active && !director.allow(&encounter, rule, budget, now) {
+                // try again once the director allows it, without rolling a new time
+                continue;
+            }
 
-        encounters.bird = encounters
-            .bird_config
-            .get(&direction)
-            .unwrap()
-            .next_spawn(now);
+            let Some(scheduled) = encounters.scheduled.get_mut(&encounter) else {
+                continue;
+            };
 
-        info!(
-            "Bird spawning at {now:.02} next bird at {:.02}",
-            encounters.bird
-        );
-    }
+            if active {
+                commands.trigger(SpawnEncounter::new(encounter.clone()));
+                scheduled.spawned += 1;
+            }
 
-    if encounters.fish < now {
-        commands.trigger(SpawnEncounter {
-            encounter_type: EncounterType::Fish,
-        });
+            scheduled.next = schedule(rule, now, scheduled.spawned, rng);
 
-        encounters.fish = encounters
-            .fish_config
-            .get(&direction)
-            .unwrap()
-            .next_spawn(now);
+            info!(
+                "{encounter} spawning at {now:.02} next {encounter} at {:.02}",
+                scheduled.next
+            );
 
-        info!(
-            "Fish spawning at {now:.02} next fish at {:.02}",
-            encounters.fish
-        );
+            // the director hears about spawns from the observer, so only spawn one a frame to
+            // let it see each before allowing the next
+            if active {
+                break;
+            }
+        }
     }
 
-    if encounters.ship < now {
-        commands.trigger(SpawnEncounter {
-            encounter_type: EncounterType::Ship,
-        });
-
-        encounters.ship = encounters
-            .ship_config
-            .get(&direction)
-            .unwrap()
-            .next_spawn(now);
-
-        if direction == TravelDirection::South {
-            // stop spawning ships after 65s when travelling South
-            if now > 65. {
-                encounters.ship = f32::MAX;
-            }
+    // one off encounters only spawn once each flip
+    encounters.one_off.retain(|encounter, time| {
+        if *time >= now {
+            return true;
         }
 
-        info!(
-            "Ship spawning at {now:.02} next ship at {:.02}",
-            encounters.ship
-        );
-    }
+        info!("{encounter} spawning at {now:.02}");
+        commands.trigger(SpawnEncounter::new(encounter.clone()));
+        false
+    });
+}
 
-    if encounters.iceberg < now {
-        commands.trigger(SpawnEncounter {
-            encounter_type: EncounterType::Iceberg,
-        });
-
-        encounters.iceberg = encounters
-            .iceberg_config
-            .get(&direction)
-            .unwrap()
-            .next_spawn(now);
-
-        if direction == TravelDirection::North {
-            // stop spawning icebergs after 40s when travelling north
-            if now > 40. {
-                encounters.iceberg = f32::MAX;
-            }
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevoids::boids::BoidRng;
+    use bevy::{state::app::StatesPlugin, time::TimeUpdateStrategy};
+
+    use super::*;
+    use crate::{
+        game::{
+            assets::TimelineKey,
+            rng::{self, VoyageSeed},
+            spawn::timeline::{self, Timelines},
+            weather::{self, Raininess},
+        },
+        screen::Screen,
+    };
+
+    /// How far each test voyage goes
+    const VOYAGE_LENGTH: f32 = 60.;
+
+    const POD: EncounterId = EncounterId::new("pod");
+
+    /// Starts after the first midnight, so it depends on the day / night clock too
+    const TIMELINES: &str = r#"(
+        timelines: [
+            (
+                name: "Pod after midnight",
+                trigger: (time_of_day: Some((start: 1.0, end: 2.0))),
+                beats: [Spawn(encounter: "pod"), Wait(2.5), Spawn(encounter: "pod")],
+            ),
+        ],
+    )"#;
+
+    /// Every spawn in a voyage, with the distance it spawned at and a number the spawn drew
+    #[derive(Resource, Default)]
+    struct SpawnLog(Vec<(EncounterId, f32, u32)>);
+
+    /// Plays the start of a voyage with `frame` between each frame
+    fn play_voyage(seed: u64, frame: Duration) -> Vec<(EncounterId, f32, u32)> {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), StatesPlugin));
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(frame));
+        app.insert_state(Screen::Title);
+        app.insert_resource(VoyageSeed(Some(seed)));
+        app.init_resource::<BoidRng>();
+        app.init_resource::<Raininess>();
+        app.init_resource::<SpawnLog>();
+
+        app.add_plugins((rng::plugin, weather::clock_plugin, plugin, timeline::plugin));
+
+        let table_driven = ["bird", "fish", "ship", "iceberg"].map(EncounterId::new);
+        let encounters = table_driven
+            .into_iter()
+            .map(|id| (id, EncounterSchedule::Table))
+            .chain([(POD, EncounterSchedule::OnDemand)]);
+        for (id, schedule) in encounters {
+            app.add_encounter(
+                id.clone(),
+                schedule,
+                move |_: In<Option<EncounterPath>>,
+                      distance: Res<TravelDistance>,
+                      mut rng: ResMut<GameRng>,
+                      mut log: ResMut<SpawnLog>| {
+                    let roll = rng.stream(RngStream::Creatures).gen();
+                    log.0.push((id.clone(), distance.get(), roll));
+                },
+            );
         }
 
-        info!(
-            "Iceberg spawning at {now:.02} next iceberg at {:.02}",
-            encounters.iceberg
-        );
+        // use the built in table rather than waiting for files to load
+        let world = app.world_mut();
+        let table = world
+            .resource_mut::<Assets<EncounterTable>>()
+            .add(EncounterTable::shipped());
+        world.insert_resource(HandleMap::from([(EncounterTableKey::Migration, table)]));
+        let timelines = world
+            .resource_mut::<Assets<Timelines>>()
+            .add(ron::de::from_str::<Timelines>(TIMELINES).unwrap());
+        world.insert_resource(HandleMap::from([(TimelineKey::Migration, timelines)]));
+
+        app.update();
+        app.world_mut()
+            .resource_mut::<NextState<Screen>>()
+            .set(Screen::Playing);
+        while app.world().resource::<TravelDistance>().get() < VOYAGE_LENGTH {
+            app.update();
+        }
+
+        let log = app.world_mut().remove_resource::<SpawnLog>().unwrap();
+        log.0
+            .into_iter()
+            .filter(|(_, at, _)| *at < VOYAGE_LENGTH)
+            .collect()
     }
 
-    if encounters.adult_whale.is_some() && encounters.adult_whale.unwrap() < now {
-        info!("Adult Whale spawning");
-        commands.trigger(SpawnEncounter {
-            encounter_type: EncounterType::AdultWhale,
-        });
+    #[test]
+    fn same_seed_replays_at_any_frame_rate() {
+        let slow = play_voyage(11, Duration::from_millis(33));
+        let fast = play_voyage(11, Duration::from_millis(7));
 
-        encounters.adult_whale = None; // only spawn one each flip
+        assert!(slow.iter().any(|(id, ..)| *id != POD));
+        assert_eq!(slow.iter().filter(|(id, ..)| *id == POD).count(), 2);
+        assert_eq!(slow, fast);
     }
 }
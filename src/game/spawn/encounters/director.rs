@@ -0,0 +1,450 @@
+//! Decides whether encounters that are due can spawn, based on how busy the screen is and what
+//! has spawned recently
+
+use std::{collections::VecDeque, fmt};
+
+use bevy::{prelude::*, utils::HashMap};
+use serde::Deserialize;
+
+use crate::game::spawn::creature::Creature;
+
+use super::{table::EncounterRule, EncounterId};
+
+/// How many recent spawns the director remembers
+const HISTORY_LENGTH: usize = 16;
+
+/// Limits on how busy the migration gets, shared by every encounter in the table.
+///
+/// ```ron
+/// budget: (
+///     max_load: Some(12.0),
+///     weights: { "fish": 0.2, "ship": 4.0 },
+///     spacing: 3.0,
+///     quiet: Some((after: 4, within: 20.0, length: 10.0)),
+/// ),
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct EncounterBudget {
+    /// The most load allowed on screen before encounters wait, where the load is the weight of
+    /// every live creature added up
+    pub max_load: Option<f32>,
+    /// How much each creature of an encounter adds to the load. Anything not listed weighs 1.
+    pub weights: HashMap<EncounterId, f32>,
+    /// The least distance between any two spawns, so encounters don't arrive all at once
+    pub spacing: f32,
+    /// A break to give after a busy stretch
+    pub quiet: Option<QuietGap>,
+}
+
+impl EncounterBudget {
+    /// How much one creature of an encounter adds to the load
+    pub fn weight(&self, encounter: &EncounterId) -> f32 {
+        self.weights.get(encounter).copied().unwrap_or(1.)
+    }
+
+    pub(super) fn validate(&self) -> Result<(), String> {
+        if let Some(max_load) = self.max_load {
+            if !max_load.is_finite() || max_load <= 0. {
+                return Err(format!("max_load must be above zero, not {max_load}"));
+            }
+        }
+
+        for (encounter, weight) in &self.weights {
+            if !weight.is_finite() || *weight < 0. {
+                return Err(format!(
+                    "the weight for {encounter} must be a positive number, not {weight}"
+                ));
+            }
+        }
+
+        if !self.spacing.is_finite() || self.spacing < 0. {
+            return Err(format!(
+                "spacing must be a positive number, not {}",
+                self.spacing
+            ));
+        }
+
+        if let Some(quiet) = &self.quiet {
+            if quiet.after == 0 || quiet.after as usize > HISTORY_LENGTH {
+                return Err(format!(
+                    "quiet must be after 1 to {HISTORY_LENGTH} spawns, not {}",
+                    quiet.after
+                ));
+            }
+
+            if !quiet.within.is_finite() || !quiet.length.is_finite() {
+                return Err(format!("quiet must have finite distances, not {quiet:?}"));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// After `after` spawns within `within` distance, no more spawn for `length`
+#[derive(Debug, Clone, Deserialize)]
+pub struct QuietGap {
+    pub after: u32,
+    pub within: f32,
+    pub length: f32,
+}
+
+/// Why an encounter that's due hasn't spawned yet
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Deferral {
+    /// Giving the whale a break after a busy stretch, until this distance
+    Quiet(f32),
+    /// Another encounter only just spawned
+    Spacing,
+    /// There are already as many of this encounter as its rule allows
+    TypeCap(u32),
+    /// The screen is as busy as the budget allows
+    Budget,
+}
+
+impl fmt::Display for Deferral {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Deferral::Quiet(until) => write!(f, "quiet until {until:.02}"),
+            Deferral::Spacing => f.write_str("too soon after the last spawn"),
+            Deferral::TypeCap(live) => write!(f, "{live} already live"),
+            Deferral::Budget => f.write_str("the screen is too busy"),
+        }
+    }
+}
+
+/// What the director last decided for an encounter
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Decision {
+    /// Spawned at this distance
+    Spawned(f32),
+    Deferred(Deferral),
+}
+
+impl fmt::Display for Decision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Decision::Spawned(at) => write!(f, "spawned at {at:.02}"),
+            Decision::Deferred(deferral) => write!(f, "waiting, {deferral}"),
+        }
+    }
+}
+
+/// Keeps the encounters from the table within the [EncounterBudget].
+///
+/// Scripted and one off encounters always spawn, but still count towards the load and the
+/// spawn history.
+#[derive(Resource, Default, Debug)]
+pub struct EncounterDirector {
+    /// How many creatures of each encounter are alive
+    live: HashMap<EncounterId, u32>,
+    /// The weighted total of live creatures
+    load: f32,
+    /// Recent spawns and the distance they spawned at, oldest first
+    history: VecDeque<(EncounterId, f32)>,
+    /// The end of the current quiet gap, if there is one
+    quiet_until: Option<f32>,
+    /// How many spawns there have been since the last quiet gap started, so one busy stretch
+    /// only earns one gap
+    since_quiet: usize,
+    decisions: HashMap<EncounterId, Decision>,
+}
+
+impl EncounterDirector {
+    /// Forgets the spawn history, ready for a new leg of the migration
+    pub fn reset(&mut self) {
+        self.history.clear();
+        self.quiet_until = None;
+        self.since_quiet = 0;
+        self.decisions.clear();
+    }
+
+    /// Gets how many creatures of an encounter are alive
+    pub fn live(&self, encounter: &EncounterId) -> u32 {
+        self.live.get(encounter).copied().unwrap_or_default()
+    }
+
+    /// Recounts the live creatures and the load they add up to
+    pub(super) fn update_live<'a>(
+        &mut self,
+        creatures: impl Iterator<Item = &'a Creature>,
+        budget: &EncounterBudget,
+    ) {
+        self.live.clear();
+        for Creature(encounter) in creatures {
+            *self.live.entry(encounter.clone()).or_default() += 1;
+        }
+
+        self.load = self
+            .live
+            .iter()
+            .map(|(encounter, count)| budget.weight(encounter) * *count as f32)
+            .sum();
+    }
+
+    /// Checks whether an encounter from the table can spawn now, remembering the answer for the
+    /// debug readout
+    pub(super) fn allow(
+        &mut self,
+        encounter: &EncounterId,
+        rule: &EncounterRule,
+        budget: &EncounterBudget,
+        now: f32,
+    ) -> bool {
+        if self.quiet_until.is_some_and(|until| now >= until) {
+            self.quiet_until = None;
+        }
+
+        let deferral = self.check(encounter, rule, budget, now);
+
+        if let Some(deferral) = deferral {
+            let decision = Decision::Deferred(deferral);
+            if self.decisions.get(encounter) != Some(&decision) {
+                info!("{encounter} waiting at {now:.02}, {deferral}");
+                self.decisions.insert(encounter.clone(), decision);
+            }
+        }
+
+        deferral.is_none()
+    }
+
+    fn check(
+        &self,
+        encounter: &EncounterId,
+        rule: &EncounterRule,
+        budget: &EncounterBudget,
+        now: f32,
+    ) -> Option<Deferral> {
+        if let Some(until) = self.quiet_until.filter(|until| now < *until) {
+            return Some(Deferral::Quiet(until));
+        }
+
+        let last_spawn = self.history.back().map(|(_, at)| *at);
+        if last_spawn.is_some_and(|at| now - at < budget.spacing) {
+            return Some(Deferral::Spacing);
+        }
+
+        let live = self.live(encounter);
+        if rule.max_live.is_some_and(|max| live >= max) {
+            return Some(Deferral::TypeCap(live));
+        }
+
+        if budget.max_load.is_some_and(|max| self.load >= max) {
+            return Some(Deferral::Budget);
+        }
+
+        None
+    }
+
+    /// Remembers that an encounter spawned, starting a quiet gap if that made for a busy stretch
+    pub(super) fn record(&mut self, encounter: &EncounterId, quiet: Option<&QuietGap>, now: f32) {
+        if self.history.len() == HISTORY_LENGTH {
+            self.history.pop_front();
+        }
+
+        self.history.push_back((encounter.clone(), now));
+        self.since_quiet += 1;
+        self.decisions
+            .insert(encounter.clone(), Decision::Spawned(now));
+
+        if let Some(quiet) = quiet {
+            // the last few spawns came close together, so take a break
+            let after = quiet.after as usize;
+            let busy = self.since_quiet >= after
+                && self
+                    .history
+                    .get(self.history.len() - after)
+                    .is_some_and(|(_, first)| now - first <= quiet.within);
+
+            if busy {
+                self.quiet_until = Some(now + quiet.length);
+                self.since_quiet = 0;
+            }
+        }
+    }
+}
+
+/// The director's state, as shown in the debug readout
+impl fmt::Display for EncounterDirector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Encounter director")?;
+        writeln!(f, "load {:.01}", self.load)?;
+        if let Some(until) = self.quiet_until {
+            writeln!(f, "quiet until {until:.02}")?;
+        }
+
+        let mut encounters: Vec<_> = self.live.keys().chain(self.decisions.keys()).collect();
+        encounters.sort_by_key(|encounter| encounter.to_string());
+        encounters.dedup();
+
+        for encounter in encounters {
+            write!(f, "{encounter}: {} live", self.live(encounter))?;
+            if let Some(decision) = self.decisions.get(encounter) {
+                write!(f, ", {decision}")?;
+            }
+            writeln!(f)?;
+        }
+
+        let recent: Vec<_> = self
+            .history
+            .iter()
+            .rev()
+            .take(5)
+            .map(|(encounter, at)| format!("{encounter} {at:.01}"))
+            .collect();
+        write!(f, "recent: {}", recent.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHIP: EncounterId = EncounterId::new("ship");
+    const WHALE: EncounterId = EncounterId::new("whale");
+
+    fn rule(max_live: Option<u32>) -> EncounterRule {
+        let mut rule: EncounterRule = ron::de::from_str(
+            "(first_spawn: 0.0, spawn_curve: (slope: 0.0, intercept: (start: 5.0, end: 6.0)))",
+        )
+        .unwrap();
+        rule.max_live = max_live;
+        rule
+    }
+
+    fn quiet_budget() -> EncounterBudget {
+        EncounterBudget {
+            quiet: Some(QuietGap {
+                after: 3,
+                within: 10.,
+                length: 20.,
+            }),
+            ..default()
+        }
+    }
+
+    /// Records a spawn with the budget's quiet gap, as the spawn observer does
+    fn record(director: &mut EncounterDirector, budget: &EncounterBudget, at: f32) {
+        director.record(&SHIP, budget.quiet.as_ref(), at);
+    }
+
+    #[test]
+    fn allows_a_quiet_screen() {
+        let mut director = EncounterDirector::default();
+        assert!(director.allow(&SHIP, &rule(None), &default(), 0.));
+    }
+
+    #[test]
+    fn defers_for_spacing() {
+        let budget = EncounterBudget {
+            spacing: 5.,
+            ..default()
+        };
+        let mut director = EncounterDirector::default();
+        record(&mut director, &budget, 10.);
+
+        assert_eq!(
+            director.check(&WHALE, &rule(None), &budget, 12.),
+            Some(Deferral::Spacing)
+        );
+        assert_eq!(director.check(&WHALE, &rule(None), &budget, 15.), None);
+    }
+
+    #[test]
+    fn defers_at_the_type_cap() {
+        let mut director = EncounterDirector::default();
+        let creatures = [Creature(SHIP), Creature(SHIP), Creature(WHALE)];
+        director.update_live(creatures.iter(), &default());
+
+        assert_eq!(
+            director.check(&SHIP, &rule(Some(2)), &default(), 0.),
+            Some(Deferral::TypeCap(2))
+        );
+        assert_eq!(director.check(&WHALE, &rule(Some(2)), &default(), 0.), None);
+    }
+
+    #[test]
+    fn defers_over_budget() {
+        let budget = EncounterBudget {
+            max_load: Some(4.),
+            weights: [(SHIP, 3.)].into_iter().collect(),
+            ..default()
+        };
+        let mut director = EncounterDirector::default();
+
+        director.update_live([Creature(WHALE)].iter(), &budget);
+        assert_eq!(director.check(&WHALE, &rule(None), &budget, 0.), None);
+
+        director.update_live([Creature(SHIP), Creature(WHALE)].iter(), &budget);
+        assert_eq!(
+            director.check(&WHALE, &rule(None), &budget, 0.),
+            Some(Deferral::Budget)
+        );
+    }
+
+    #[test]
+    fn quiet_gap_after_a_busy_stretch() {
+        let budget = quiet_budget();
+        let mut director = EncounterDirector::default();
+
+        // spread out spawns don't count as busy
+        for at in [0., 20., 40.] {
+            record(&mut director, &budget, at);
+        }
+        assert_eq!(director.check(&SHIP, &rule(None), &budget, 41.), None);
+
+        for at in [42., 44.] {
+            record(&mut director, &budget, at);
+        }
+        assert_eq!(
+            director.check(&SHIP, &rule(None), &budget, 50.),
+            Some(Deferral::Quiet(64.))
+        );
+
+        // checking doesn't change anything
+        assert_eq!(
+            director.check(&SHIP, &rule(None), &budget, 50.),
+            Some(Deferral::Quiet(64.))
+        );
+    }
+
+    #[test]
+    fn quiet_gap_resets() {
+        let budget = quiet_budget();
+        let mut director = EncounterDirector::default();
+        for at in [0., 1., 2.] {
+            record(&mut director, &budget, at);
+        }
+        assert!(!director.allow(&SHIP, &rule(None), &budget, 21.));
+
+        // the gap is over, and the spawns before it don't start another one
+        assert!(director.allow(&SHIP, &rule(None), &budget, 22.));
+        assert_eq!(director.quiet_until, None);
+        for at in [22., 23.] {
+            record(&mut director, &budget, at);
+        }
+        assert!(director.allow(&SHIP, &rule(None), &budget, 24.));
+
+        record(&mut director, &budget, 24.);
+        assert_eq!(
+            director.check(&SHIP, &rule(None), &budget, 25.),
+            Some(Deferral::Quiet(44.))
+        );
+    }
+
+    #[test]
+    fn history_rolls_over() {
+        let mut director = EncounterDirector::default();
+        for at in 0..HISTORY_LENGTH + 4 {
+            director.record(&SHIP, None, at as f32);
+        }
+
+        assert_eq!(director.history.len(), HISTORY_LENGTH);
+        assert_eq!(director.history.front().map(|(_, at)| *at), Some(4.));
+        assert_eq!(
+            director.history.back().map(|(_, at)| *at),
+            Some((HISTORY_LENGTH + 3) as f32)
+        );
+    }
+}
@@ -0,0 +1,325 @@
+//! Encounter spawn rates, loaded from `.encounters.ron` files so they can be balanced without
+//! recompiling
+
+use std::{fmt, ops::Range};
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    utils::HashMap,
+};
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::game::weather::TravelDirection;
+
+use super::{director::EncounterBudget, EncounterId};
+
+/// The table the game ships with, for when the one on disk can't be loaded
+const SHIPPED_TABLE: &str = include_str!("../../../../assets/encounters/migration.encounters.ron");
+
+/// How often each encounter spawns on each leg of the migration.
+///
+/// Encounters are listed by their [EncounterId]. Encounters that aren't listed for a direction
+/// never spawn going that way. The optional `budget` limits how busy the screen gets.
+///
+/// ```ron
+/// (
+///     encounters: {
+///         "ship": {
+///             North: (
+///                 first_spawn: 45.0,
+///                 spawn_curve: (slope: -24.0, intercept: (start: 40.0, end: 50.0)),
+///             ),
+///             South: (
+///                 first_spawn: 45.0,
+///                 spawn_curve: (slope: -24.0, intercept: (start: 40.0, end: 50.0)),
+///                 active: Some((start: 0.0, end: 65.0)),
+///                 max_spawns: Some(2),
+///                 max_live: Some(1),
+///             ),
+///         },
+///     },
+///     budget: (max_load: Some(12.0), spacing: 3.0),
+/// )
+/// ```
+#[derive(Asset, TypePath, Debug, Clone, Default, Deserialize)]
+pub struct EncounterTable {
+    encounters: HashMap<EncounterId, HashMap<TravelDirection, EncounterRule>>,
+    #[serde(default)]
+    budget: EncounterBudget,
+}
+
+impl EncounterTable {
+    /// The table the game was built with, or an empty one if even that doesn't make sense
+    pub fn shipped() -> Self {
+        ron::de::from_str::<Self>(SHIPPED_TABLE)
+            .ok()
+            .filter(|table| table.validate().is_ok())
+            .unwrap_or_default()
+    }
+
+    /// Gets the rule for an encounter when travelling in a direction
+    pub fn rule(
+        &self,
+        encounter: &EncounterId,
+        direction: TravelDirection,
+    ) -> Option<&EncounterRule> {
+        self.encounters.get(encounter)?.get(&direction)
+    }
+
+    /// Gets the limits on how busy the screen gets
+    pub fn budget(&self) -> &EncounterBudget {
+        &self.budget
+    }
+
+    /// Gets every encounter listed in the table
+    pub fn encounters(&self) -> impl Iterator<Item = &EncounterId> {
+        self.encounters.keys()
+    }
+
+    /// Checks the table makes sense, so mistakes are caught when it loads rather than when an
+    /// encounter tries to spawn
+    fn validate(&self) -> Result<(), String> {
+        for (encounter, rules) in &self.encounters {
+            for (direction, rule) in rules {
+                rule.validate()
+                    .map_err(|err| format!("{encounter} going {direction:?}: {err}"))?;
+            }
+        }
+
+        self.budget
+            .validate()
+            .map_err(|err| format!("budget: {err}"))
+    }
+}
+
+/// When and how often an encounter spawns on one leg of the migration. All times are the
+/// distance travelled since the last flip.
+#[derive(Debug, Clone, Deserialize)]
+pub struct EncounterRule {
+    /// When the first one spawns
+    pub first_spawn: f32,
+    pub spawn_curve: EncounterSpawnCurve,
+    /// Only spawn between these times. Spawns due before the window opens wait for it.
+    #[serde(default)]
+    pub active: Option<Range<f32>>,
+    /// The most that can spawn before the next flip
+    #[serde(default)]
+    pub max_spawns: Option<u32>,
+    /// The most creatures from this encounter that can be on screen before another spawns
+    #[serde(default)]
+    pub max_live: Option<u32>,
+}
+
+impl EncounterRule {
+    fn validate(&self) -> Result<(), String> {
+        if !self.first_spawn.is_finite() || self.first_spawn < 0. {
+            return Err(format!(
+                "first_spawn must be a positive number, not {}",
+                self.first_spawn
+            ));
+        }
+
+        if let Some(active) = &self.active {
+            check_range("active", active)?;
+        }
+
+        self.spawn_curve.validate()
+    }
+}
+
+/// How long to wait between spawns, which changes linearly with how far the whale has travelled
+#[derive(Debug, Clone, Deserialize)]
+pub struct EncounterSpawnCurve {
+    /// How much the gap changes, in percent of the distance travelled
+    pub slope: f32,
+    /// The gap at the start of the leg, picked at random from this range
+    pub intercept: Range<f32>,
+    /// The shortest and longest the gap can be
+    #[serde(default = "default_gap")]
+    pub gap: Range<f32>,
+}
+
+fn default_gap() -> Range<f32> {
+    1.0..100.0
+}
+
+impl EncounterSpawnCurve {
+    /// Picks when the next spawn is, given the last one was at `t`
+    pub fn next_spawn(&self, t: f32, rng: &mut impl Rng) -> f32 {
+        let intercept = rng.gen_range(self.intercept.clone());
+        t + ((t * self.slope / 100.) + intercept).clamp(self.gap.start, self.gap.end)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if !self.slope.is_finite() {
+            return Err(format!("slope must be a number, not {}", self.slope));
+        }
+
+        check_range("intercept", &self.intercept)?;
+        check_range("gap", &self.gap)?;
+
+        // a gap of zero would spawn one every frame
+        if self.gap.start <= 0. {
+            return Err(format!("gap must start above zero, not {}", self.gap.start));
+        }
+
+        Ok(())
+    }
+}
+
+fn check_range(name: &str, range: &Range<f32>) -> Result<(), String> {
+    if !range.start.is_finite() || !range.end.is_finite() || range.is_empty() {
+        return Err(format!(
+            "{name} must have a start before its end, not {:?}",
+            range
+        ));
+    }
+
+    Ok(())
+}
+
+/// Loads [EncounterTable]s from `.encounters.ron` files
+#[derive(Default)]
+pub struct EncounterTableLoader;
+
+/// The errors that can happen while loading an [EncounterTable]
+#[derive(Debug)]
+pub enum EncounterTableLoaderError {
+    Io(std::io::Error),
+    Ron(ron::error::SpannedError),
+    Invalid(String),
+}
+
+impl fmt::Display for EncounterTableLoaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncounterTableLoaderError::Io(err) => {
+                write!(f, "could not read encounter table: {err}")
+            }
+            EncounterTableLoaderError::Ron(err) => {
+                write!(f, "could not parse encounter table: {err}")
+            }
+            EncounterTableLoaderError::Invalid(err) => {
+                write!(f, "invalid encounter table: {err}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for EncounterTableLoaderError {}
+
+impl From<std::io::Error> for EncounterTableLoaderError {
+    fn from(err: std::io::Error) -> Self {
+        EncounterTableLoaderError::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for EncounterTableLoaderError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        EncounterTableLoaderError::Ron(err)
+    }
+}
+
+impl AssetLoader for EncounterTableLoader {
+    type Asset = EncounterTable;
+    type Settings = ();
+    type Error = EncounterTableLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let table: EncounterTable = ron::de::from_bytes(&bytes)?;
+        table
+            .validate()
+            .map_err(EncounterTableLoaderError::Invalid)?;
+
+        Ok(table)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["encounters.ron"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Validates a table with one ship rule, plus whatever budget is given
+    fn validate_ship(rule: &str, budget: &str) -> Result<(), String> {
+        let table = format!("(encounters: {{ \"ship\": {{ North: {rule} }} }}, budget: {budget})");
+        ron::de::from_str::<EncounterTable>(&table)
+            .unwrap_or_else(|err| panic!("couldn't parse {table}: {err}"))
+            .validate()
+    }
+
+    fn validate_rule(rule: &str) -> Result<(), String> {
+        validate_ship(rule, "()")
+    }
+
+    fn validate_budget(budget: &str) -> Result<(), String> {
+        validate_ship(
+            "(first_spawn: 10.0, spawn_curve: (slope: 0.0, intercept: (start: 5.0, end: 6.0)))",
+            budget,
+        )
+    }
+
+    #[test]
+    fn shipped_table_is_valid() {
+        let table: EncounterTable = ron::de::from_str(SHIPPED_TABLE).unwrap();
+        table.validate().unwrap();
+        assert!(EncounterTable::shipped().encounters().count() > 0);
+    }
+
+    #[test]
+    fn accepts_a_simple_table() {
+        validate_rule(
+            "(first_spawn: 10.0, spawn_curve: (slope: -24.0, intercept: (start: 5.0, end: 6.0)))",
+        )
+        .unwrap();
+        validate_budget("(max_load: Some(10.0), weights: { \"ship\": 4.0 }, spacing: 2.0)")
+            .unwrap();
+    }
+
+    #[test]
+    fn rejects_bad_rules() {
+        for rule in [
+            // spawns before the leg starts
+            "(first_spawn: -1.0, spawn_curve: (slope: 0.0, intercept: (start: 5.0, end: 6.0)))",
+            "(first_spawn: inf, spawn_curve: (slope: 0.0, intercept: (start: 5.0, end: 6.0)))",
+            // the window closes before it opens
+            "(first_spawn: 1.0, spawn_curve: (slope: 0.0, intercept: (start: 5.0, end: 6.0)), active: Some((start: 50.0, end: 10.0)))",
+            "(first_spawn: 1.0, spawn_curve: (slope: NaN, intercept: (start: 5.0, end: 6.0)))",
+            // nothing to pick the gap from
+            "(first_spawn: 1.0, spawn_curve: (slope: 0.0, intercept: (start: 6.0, end: 6.0)))",
+            "(first_spawn: 1.0, spawn_curve: (slope: 0.0, intercept: (start: 5.0, end: 6.0), gap: (start: 10.0, end: 1.0)))",
+            // would spawn every frame
+            "(first_spawn: 1.0, spawn_curve: (slope: 0.0, intercept: (start: 5.0, end: 6.0), gap: (start: 0.0, end: 10.0)))",
+        ] {
+            assert!(validate_rule(rule).is_err(), "accepted {rule}");
+        }
+    }
+
+    #[test]
+    fn rejects_bad_budgets() {
+        for budget in [
+            "(max_load: Some(0.0))",
+            "(max_load: Some(inf))",
+            "(weights: { \"ship\": -1.0 })",
+            "(spacing: -1.0)",
+            "(quiet: Some((after: 0, within: 10.0, length: 10.0)))",
+            "(quiet: Some((after: 17, within: 10.0, length: 10.0)))",
+            "(quiet: Some((after: 2, within: inf, length: 10.0)))",
+        ] {
+            assert!(validate_budget(budget).is_err(), "accepted {budget}");
+        }
+    }
+}
@@ -11,6 +11,7 @@ pub mod creature;
 pub mod encounters;
 pub mod level;
 pub mod player;
+pub mod timeline;
 
 #[derive(Resource)]
 pub struct WindowSize {
@@ -65,8 +66,7 @@ impl WindowSize {
         )
     }
 
-    pub fn get_random_position(&self) -> Vec2 {
-        let mut rng = rand::thread_rng();
+    pub fn get_random_position(&self, rng: &mut impl Rng) -> Vec2 {
         let half = self.half();
         Vec2::new(
             rng.gen_range(-half.x..half.x),
@@ -74,8 +74,7 @@ impl WindowSize {
         )
     }
 
-    pub fn get_random_position_outside(&self) -> Vec2 {
-        let mut rng = rand::thread_rng();
+    pub fn get_random_position_outside(&self, rng: &mut impl Rng) -> Vec2 {
         let half = self.half();
 
         if rng.gen_bool(0.5) {
@@ -85,7 +84,7 @@ impl WindowSize {
                     -half.x - 2. * WINDOW_DESPAWN_BUFFER,
                     half.x + 2. * WINDOW_DESPAWN_BUFFER,
                 ]
-                .choose(&mut rng)
+                .choose(rng)
                 .unwrap_or(&(half.x + 2. * WINDOW_DESPAWN_BUFFER)),
                 rng.gen_range(-half.y..half.y),
             )
@@ -97,7 +96,7 @@ impl WindowSize {
                     -half.y - 2. * WINDOW_DESPAWN_BUFFER,
                     half.y + 2. * WINDOW_DESPAWN_BUFFER,
                 ]
-                .choose(&mut rng)
+                .choose(rng)
                 .unwrap_or(&(half.y + 2. * WINDOW_DESPAWN_BUFFER)),
             )
         }
@@ -110,6 +109,7 @@ pub(super) fn plugin(app: &mut App) {
         level::plugin,
         player::plugin,
         creature::plugin,
+        timeline::plugin,
     ));
 
     app.insert_resource(WindowSize { size: Vec2::ONE })
@@ -2,7 +2,6 @@
 
 use std::time::Duration;
 
-use bevoids::boids::BoidRepulsor;
 use bevy::prelude::*;
 use rand::Rng;
 
@@ -18,12 +17,16 @@ use crate::{
             MoveWithVelocity, Movement, MovementIntent, PlayerActionRequested,
             WHALE_SCREEN_BUFFER_FRACTION, WHALE_TRAVEL_SPEED,
         },
+        rng::{GameRng, RngStream},
         weather::Wave,
     },
     screen::Screen,
 };
 
-use super::{creature::Ship, WindowSize};
+use super::{
+    creature::{boid::get_whale_predator, Ship},
+    WindowSize,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.observe(spawn_player).observe(handle_player_action);
@@ -97,9 +100,10 @@ fn spawn_breaths(
     time: Res<Time>,
     image_handles: Res<HandleMap<ImageKey>>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut rng: ResMut<GameRng>,
     mut breaths: Query<(Entity, &mut BreathingTimer)>,
 ) {
-    let mut rng = rand::thread_rng();
+    let rng = rng.stream(RngStream::Player);
 
     for (whale_entity, mut breath) in &mut breaths {
         breath.timer.tick(time.delta());
@@ -161,10 +165,7 @@ fn spawn_player(
         .spawn((
             Name::new("Player"),
             Whale,
-            BoidRepulsor {
-                strength: 0.85,
-                range: 80.,
-            },
+            get_whale_predator(),
             SpriteBundle {
                 texture: image_handles[&ImageKey::Creatures].clone_weak(),
                 transform: Transform::from_translation(start_pos),
@@ -0,0 +1,453 @@
+//! Scripted set pieces, loaded from `.timeline.ron` files. Each timeline starts when its trigger
+//! matches, then plays its beats in order.
+
+use std::{fmt, ops::Range};
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    utils::HashSet,
+};
+use serde::Deserialize;
+
+use crate::{
+    game::{
+        assets::{HandleMap, SfxKey, TimelineKey},
+        audio::sfx::PlaySfx,
+        rng::{VoyageSet, NEW_VOYAGE},
+        weather::{Raininess, TravelDirection, TravelDistance, WeatherState},
+    },
+    screen::{Screen, UiFadeComplete, UiImageFadeInOut},
+};
+
+use super::encounters::{EncounterId, EncounterPath, EncounterRegistry, SpawnEncounter};
+
+/// How long narrative text takes to fade in and out, in seconds
+const NARRATIVE_FADE: f32 = 0.8;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<Timelines>();
+    app.init_asset_loader::<TimelinesLoader>();
+
+    // the timelines can only be loaded once their asset type is registered
+    app.register_type::<HandleMap<TimelineKey>>();
+    app.init_resource::<HandleMap<TimelineKey>>();
+
+    app.init_resource::<TimelineRunner>();
+    app.add_systems(NEW_VOYAGE, reset_timelines);
+    app.add_systems(Update, check_timelines);
+    app.add_systems(FixedUpdate, run_timelines.in_set(VoyageSet::Timelines));
+}
+
+/// A set of scripted moments.
+///
+/// ```ron
+/// (
+///     timelines: [
+///         (
+///             name: "Whale pod at dawn",
+///             trigger: (
+///                 direction: Some(South),
+///                 flip: Some(5),
+///                 after_distance: Some(10.0),
+///                 time_of_day: Some((start: 5.0, end: 7.0)),
+///             ),
+///             beats: [
+///                 Text(text: "A pod of humpbacks passes by", duration: 5.0),
+///                 Sfx(AdultWhaleSong),
+///                 Spawn(encounter: "adult_whale", path: Some((from: (-0.7, -1.4), to: (-0.7, 1.4)))),
+///                 Wait(1.5),
+///                 Spawn(encounter: "adult_whale"),
+///             ],
+///         ),
+///     ],
+/// )
+/// ```
+#[derive(Asset, TypePath, Debug, Clone, Deserialize)]
+pub struct Timelines {
+    pub timelines: Vec<Timeline>,
+}
+
+/// A scripted moment, which plays at most once on each leg of the migration
+#[derive(Debug, Clone, Deserialize)]
+pub struct Timeline {
+    /// Tells the timelines apart, so it has to be unique
+    pub name: String,
+    /// When the timeline starts
+    pub trigger: TimelineCondition,
+    pub beats: Vec<TimelineBeat>,
+}
+
+/// Something that's true at a point in the migration. Every part that's given has to match.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct TimelineCondition {
+    pub direction: Option<TravelDirection>,
+    /// Which leg of the migration it is, counting from 0 for the first leg North
+    pub flip: Option<u32>,
+    /// How far the whale has to have travelled on this leg
+    pub after_distance: Option<f32>,
+    /// The hours of the day, from 0 to 24. The range can wrap past midnight, e.g. 22 to 2.
+    pub time_of_day: Option<Range<f32>>,
+    pub weather: Option<TimelineWeather>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum TimelineWeather {
+    Raining,
+    Dry,
+    Sunny,
+    Stormy,
+}
+
+/// A single step in a [Timeline]
+#[derive(Debug, Clone, Deserialize)]
+pub enum TimelineBeat {
+    /// Spawns an encounter, optionally along a set path
+    Spawn {
+        encounter: EncounterId,
+        #[serde(default)]
+        path: Option<EncounterPath>,
+    },
+    /// Plays a sound effect
+    Sfx(SfxKey),
+    /// Shows some text on screen for `duration` seconds
+    Text { text: String, duration: f32 },
+    /// Waits until the whale has travelled this much further
+    Wait(f32),
+    /// Waits until the condition matches
+    WaitUntil(TimelineCondition),
+}
+
+/// What's going on in the migration right now, for checking [TimelineCondition]s against
+struct MigrationState {
+    direction: TravelDirection,
+    flip: u32,
+    distance: f32,
+    time_of_day: f32,
+    is_raining: bool,
+    is_sunny: bool,
+}
+
+impl TimelineCondition {
+    fn matches(&self, state: &MigrationState) -> bool {
+        let time_of_day = self.time_of_day.as_ref().is_none_or(|hours| {
+            if hours.start <= hours.end {
+                hours.contains(&state.time_of_day)
+            } else {
+                state.time_of_day >= hours.start || state.time_of_day < hours.end
+            }
+        });
+
+        let weather = self.weather.is_none_or(|weather| match weather {
+            TimelineWeather::Raining => state.is_raining,
+            TimelineWeather::Dry => !state.is_raining,
+            TimelineWeather::Sunny => state.is_sunny,
+            TimelineWeather::Stormy => !state.is_sunny,
+        });
+
+        self.direction.is_none_or(|dir| dir == state.direction)
+            && self.flip.is_none_or(|flip| flip == state.flip)
+            && self
+                .after_distance
+                .is_none_or(|distance| state.distance >= distance)
+            && time_of_day
+            && weather
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if let Some(distance) = self.after_distance {
+            if !distance.is_finite() {
+                return Err(format!("after_distance must be a number, not {distance}"));
+            }
+        }
+
+        if let Some(hours) = &self.time_of_day {
+            let in_day = |hour: f32| (0.0..=24.0).contains(&hour);
+            if !in_day(hours.start) || !in_day(hours.end) {
+                return Err(format!(
+                    "time_of_day must be between 0 and 24 hours, not {hours:?}"
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Timelines {
+    /// Checks the timelines make sense, so mistakes are caught when they load rather than part
+    /// way through a voyage
+    fn validate(&self) -> Result<(), String> {
+        let mut names = HashSet::default();
+
+        for timeline in &self.timelines {
+            let name = &timeline.name;
+            if !names.insert(name) {
+                return Err(format!("there's more than one timeline called {name}"));
+            }
+
+            timeline
+                .trigger
+                .validate()
+                .map_err(|err| format!("{name} trigger: {err}"))?;
+
+            for (idx, beat) in timeline.beats.iter().enumerate() {
+                match beat {
+                    TimelineBeat::Text { duration, .. }
+                        if !duration.is_finite() || *duration <= 0. =>
+                    {
+                        return Err(format!(
+                            "{name} beat {idx}: text duration must be above zero, not {duration}"
+                        ));
+                    }
+                    TimelineBeat::Wait(distance) if !distance.is_finite() || *distance < 0. => {
+                        return Err(format!(
+                            "{name} beat {idx}: wait must be a positive number, not {distance}"
+                        ));
+                    }
+                    TimelineBeat::WaitUntil(condition) => condition
+                        .validate()
+                        .map_err(|err| format!("{name} beat {idx}: {err}"))?,
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Loads [Timelines] from `.timeline.ron` files
+#[derive(Default)]
+pub struct TimelinesLoader;
+
+/// The errors that can happen while loading [Timelines]
+#[derive(Debug)]
+pub enum TimelinesLoaderError {
+    Io(std::io::Error),
+    Ron(ron::error::SpannedError),
+    Invalid(String),
+}
+
+impl fmt::Display for TimelinesLoaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimelinesLoaderError::Io(err) => write!(f, "could not read timelines: {err}"),
+            TimelinesLoaderError::Ron(err) => write!(f, "could not parse timelines: {err}"),
+            TimelinesLoaderError::Invalid(err) => write!(f, "invalid timelines: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for TimelinesLoaderError {}
+
+impl From<std::io::Error> for TimelinesLoaderError {
+    fn from(err: std::io::Error) -> Self {
+        TimelinesLoaderError::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for TimelinesLoaderError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        TimelinesLoaderError::Ron(err)
+    }
+}
+
+impl AssetLoader for TimelinesLoader {
+    type Asset = Timelines;
+    type Settings = ();
+    type Error = TimelinesLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let timelines: Timelines = ron::de::from_bytes(&bytes)?;
+        timelines
+            .validate()
+            .map_err(TimelinesLoaderError::Invalid)?;
+
+        Ok(timelines)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["timeline.ron"]
+    }
+}
+
+/// A timeline part way through playing
+struct RunningTimeline {
+    timeline: usize,
+    /// The leg it started on. Timelines stop when the whale flips.
+    flip: u32,
+    beat: usize,
+    /// The distance to wait for before playing the next beat
+    resume_at: f32,
+}
+
+#[derive(Resource, Default)]
+struct TimelineRunner {
+    /// The timelines that have played, by name and the leg they played on, so editing the file
+    /// doesn't replay them
+    played: HashSet<(String, u32)>,
+    running: Vec<RunningTimeline>,
+}
+
+fn reset_timelines(mut runner: ResMut<TimelineRunner>) {
+    *runner = TimelineRunner::default();
+}
+
+/// Warns about spawn beats for encounters the game doesn't know how to spawn
+fn check_timelines(
+    mut events: EventReader<AssetEvent<Timelines>>,
+    assets: Res<Assets<Timelines>>,
+    registry: Res<EncounterRegistry>,
+) {
+    for event in events.read() {
+        let (AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }) = event
+        else {
+            continue;
+        };
+        let Some(timelines) = assets.get(*id) else {
+            continue;
+        };
+
+        for timeline in &timelines.timelines {
+            for beat in &timeline.beats {
+                if let TimelineBeat::Spawn { encounter, .. } = beat {
+                    if registry.schedule(encounter).is_none() {
+                        warn!(
+                            "No encounter called {encounter} has been registered, the {} timeline won't spawn it",
+                            timeline.name
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn run_timelines(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<Timelines>>,
+    handles: Res<HandleMap<TimelineKey>>,
+    assets: Res<Assets<Timelines>>,
+    distance: Res<TravelDistance>,
+    weather: Res<WeatherState>,
+    raininess: Res<Raininess>,
+    mut runner: ResMut<TimelineRunner>,
+) {
+    let handle = &handles[&TimelineKey::Migration];
+
+    // beats may have moved around, so stop rather than pick up somewhere odd
+    if events.read().any(|event| event.is_modified(handle.id())) {
+        info!("Timelines changed, stopping any that are running");
+        runner.running.clear();
+    }
+
+    let Some(timelines) = assets.get(handle) else {
+        return;
+    };
+
+    let state = MigrationState {
+        direction: distance.travel_direction(),
+        flip: distance.get_flip_number(),
+        distance: distance.get(),
+        time_of_day: weather.time_of_day,
+        is_raining: raininess.is_raining(),
+        is_sunny: weather.is_sunny,
+    };
+
+    runner.running.retain(|running| running.flip == state.flip);
+
+    for (idx, timeline) in timelines.timelines.iter().enumerate() {
+        let played = (timeline.name.clone(), state.flip);
+        if runner.played.contains(&played) || !timeline.trigger.matches(&state) {
+            continue;
+        }
+
+        info!(
+            "Starting timeline {} at {:.02}",
+            timeline.name, state.distance
+        );
+        runner.played.insert(played);
+        runner.running.push(RunningTimeline {
+            timeline: idx,
+            flip: state.flip,
+            beat: 0,
+            resume_at: state.distance,
+        });
+    }
+
+    runner.running.retain_mut(|running| {
+        let Some(timeline) = timelines.timelines.get(running.timeline) else {
+            return false;
+        };
+
+        while running.resume_at <= state.distance {
+            let Some(beat) = timeline.beats.get(running.beat) else {
+                info!("Finished timeline {}", timeline.name);
+                return false;
+            };
+
+            match beat {
+                TimelineBeat::Spawn { encounter, path } => {
+                    let spawn = SpawnEncounter::new(encounter.clone());
+                    commands.trigger(match path {
+                        Some(path) => spawn.with_path(*path),
+                        None => spawn,
+                    });
+                }
+                TimelineBeat::Sfx(key) => commands.trigger(PlaySfx::once(*key)),
+                TimelineBeat::Text { text, duration } => {
+                    spawn_narrative_text(&mut commands, text, *duration)
+                }
+                TimelineBeat::Wait(wait) => running.resume_at = state.distance + wait,
+                TimelineBeat::WaitUntil(condition) => {
+                    if !condition.matches(&state) {
+                        return true;
+                    }
+                }
+            }
+
+            running.beat += 1;
+        }
+
+        true
+    });
+}
+
+fn spawn_narrative_text(commands: &mut Commands, text: &str, duration: f32) {
+    commands
+        .spawn((
+            Name::new("Narrative Text"),
+            TextBundle::from_section(
+                text,
+                TextStyle {
+                    font_size: 24.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            )
+            .with_text_justify(JustifyText::Center)
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                top: Val::Percent(15.),
+                width: Val::Percent(100.),
+                ..default()
+            }),
+            UiImageFadeInOut::new(NARRATIVE_FADE.min(duration / 2.), duration),
+            StateScoped(Screen::Playing),
+        ))
+        .observe(despawn_when_faded);
+}
+
+fn despawn_when_faded(trigger: Trigger<UiFadeComplete>, mut commands: Commands) {
+    commands.entity(trigger.entity()).despawn_recursive();
+}
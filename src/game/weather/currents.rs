@@ -0,0 +1,55 @@
+use bevoids::flow::{FlowField, FlowPattern};
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    game::rng::{GameRng, RngStream},
+    screen::Screen,
+};
+
+use super::{TravelDistance, DISTANCE_FLIPPING};
+
+/// How fast the current runs across the screen in the open ocean, in pixels per second
+const OPEN_OCEAN_CURRENT: f32 = 8.;
+
+/// How fast the swirls in the water are
+const EDDY_STRENGTH: f32 = 5.;
+
+/// How big the swirls in the water are, smaller is bigger
+const EDDY_FREQUENCY: f32 = 0.004;
+
+/// How fast the swirls drift down the screen as the whale swims
+const EDDY_SCROLL_SPEED: f32 = 0.05;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Playing), set_up_currents);
+    app.add_systems(OnExit(Screen::Playing), remove_currents);
+    app.add_systems(Update, update_currents.run_if(in_state(Screen::Playing)));
+}
+
+fn set_up_currents(mut commands: Commands, mut rng: ResMut<GameRng>) {
+    commands.insert_resource(FlowField {
+        current: Vec3::ZERO,
+        pattern: FlowPattern::CurlNoise {
+            frequency: EDDY_FREQUENCY,
+            strength: EDDY_STRENGTH,
+            offset: Vec2::ZERO,
+            seed: rng.stream(RngStream::Weather).gen(),
+        },
+    });
+}
+
+fn remove_currents(mut commands: Commands) {
+    commands.remove_resource::<FlowField>();
+}
+
+/// The current is calm near the coasts at either end of the migration and strongest half way
+fn update_currents(time: Res<Time>, distance: Res<TravelDistance>, mut flow: ResMut<FlowField>) {
+    let latitude = (distance.get() % DISTANCE_FLIPPING) / DISTANCE_FLIPPING;
+
+    flow.current = Vec3::X * OPEN_OCEAN_CURRENT * (latitude * std::f32::consts::PI).sin();
+
+    if let FlowPattern::CurlNoise { offset, .. } = &mut flow.pattern {
+        offset.y += EDDY_SCROLL_SPEED * time.delta_seconds();
+    }
+}
@@ -5,7 +5,10 @@
 use bevy::prelude::*;
 use rand::Rng;
 
-use crate::screen::Screen;
+use crate::{
+    game::rng::{GameRng, RngStream, VoyageSet, NEW_VOYAGE},
+    screen::Screen,
+};
 
 // The amount of world time that elapses per game second
 const TIME_OF_DAY_HOURS_PER_GAME_SECONDS: f32 = 1.2;
@@ -65,32 +68,40 @@ impl Default for WeatherState {
 }
 
 pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(DayNightColour(Color::srgb(
+        SUNNY_COLOR_CYCLE[3].x,
+        SUNNY_COLOR_CYCLE[3].y,
+        SUNNY_COLOR_CYCLE[3].z,
+    )))
+    .add_systems(
+        Update,
+        (day_night_cycle, tint_with_day_night_cycle)
+            .chain()
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+/// Keeps the time of day, which moves on with the voyage's fixed tick
+pub(super) fn clock_plugin(app: &mut App) {
     app.init_resource::<WeatherState>()
-        .insert_resource(DayNightColour(Color::srgb(
-            SUNNY_COLOR_CYCLE[3].x,
-            SUNNY_COLOR_CYCLE[3].y,
-            SUNNY_COLOR_CYCLE[3].z,
-        )))
-        .add_systems(
-            Update,
-            (day_night_cycle, tint_with_day_night_cycle)
-                .chain()
-                .run_if(in_state(Screen::Playing)),
-        );
+        .add_systems(FixedUpdate, advance_time_of_day.in_set(VoyageSet::Weather))
+        .add_systems(NEW_VOYAGE, reset_weather);
+}
+
+fn reset_weather(mut weather: ResMut<WeatherState>) {
+    *weather = WeatherState::default();
 }
 
 #[derive(Resource)]
 pub struct DayNightColour(Color);
 
-fn day_night_cycle(
+fn advance_time_of_day(
     time: Res<Time>,
-    mut dnc: ResMut<DayNightColour>,
     mut weather: ResMut<WeatherState>,
-    mut clear_colour: ResMut<ClearColor>,
+    mut rng: ResMut<GameRng>,
 ) {
     let dt = time.delta_seconds();
     let elapsed = dt * TIME_OF_DAY_HOURS_PER_GAME_SECONDS;
-    let mut rng = rand::thread_rng();
 
     let prev_time_of_day = weather.time_of_day;
     weather.time_of_day = (weather.time_of_day + elapsed) % 24.0;
@@ -98,9 +109,15 @@ fn day_night_cycle(
     // check if we've wrapped over midnight
     if prev_time_of_day > 23.0 && weather.time_of_day < 1.0 {
         // true if we've just wrapped day, we need to toggle the colour pattern
-        weather.is_sunny = rng.gen_bool(CHANCE_OF_SUN);
+        weather.is_sunny = rng.stream(RngStream::Weather).gen_bool(CHANCE_OF_SUN);
     }
+}
 
+fn day_night_cycle(
+    weather: Res<WeatherState>,
+    mut dnc: ResMut<DayNightColour>,
+    mut clear_colour: ResMut<ClearColor>,
+) {
     let from_idx = (weather.time_of_day / HOURS_PER_COLOUR).floor() as usize;
     let to_idx = (from_idx + 1) % NUM_COLOURS;
 
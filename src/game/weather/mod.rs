@@ -4,9 +4,14 @@ use std::ops::Range;
 
 use bevy::prelude::*;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
-use crate::{game::flipper::DoFlip, screen::Screen};
+use crate::{
+    game::{flipper::DoFlip, rng::VoyageSet},
+    screen::Screen,
+};
 
+mod currents;
 mod day_night_cycle;
 mod rain;
 mod waves;
@@ -24,7 +29,7 @@ const FLIP_MESSAGES: [&str; 2] = [
     "The Southern waters are ideal for summer.\n\nThe migration cycle will continue when the water cools.",
 ];
 
-#[derive(Copy, Clone, Hash, PartialEq, Eq, Debug)]
+#[derive(Copy, Clone, Hash, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub enum TravelDirection {
     North,
     South,
@@ -50,8 +55,8 @@ impl TravelDistance {
     }
 
     /// Generates a future time within `now + range`
-    pub fn future_range(&self, range: Range<f32>) -> f32 {
-        self.distance + rand::thread_rng().gen_range(range)
+    pub fn future_range(&self, range: Range<f32>, rng: &mut impl Rng) -> f32 {
+        self.distance + rng.gen_range(range)
     }
 
     /// resets the distance travelled to 0
@@ -88,16 +93,27 @@ impl TravelDistance {
 }
 
 pub(super) fn plugin(app: &mut App) {
+    app.add_plugins((
+        clock_plugin,
+        currents::plugin,
+        day_night_cycle::plugin,
+        rain::plugin,
+        waves::plugin,
+    ));
+}
+
+/// Moves the voyage along each fixed tick, through the distance travelled and the time of day
+pub(super) fn clock_plugin(app: &mut App) {
     app.insert_resource(TravelDistance::default());
 
     app.add_systems(OnEnter(Screen::Playing), reset_travel_distance);
 
     app.add_systems(
-        Update,
-        update_travel_distance.run_if(in_state(Screen::Playing)),
+        FixedUpdate,
+        update_travel_distance.in_set(VoyageSet::Travel),
     );
 
-    app.add_plugins((day_night_cycle::plugin, rain::plugin, waves::plugin));
+    app.add_plugins(day_night_cycle::clock_plugin);
 }
 
 fn reset_travel_distance(mut distance: ResMut<TravelDistance>) {
@@ -8,6 +8,7 @@ use crate::{
         animation::{despawn_when_animation_complete, SpriteAnimationPlayer},
         assets::{HandleMap, ImageKey, SfxKey},
         audio::sfx::{FadeIn, FadeOut, PlaySfx},
+        rng::{GameRng, RngStream, VoyageSet, NEW_VOYAGE},
         spawn::WindowSize,
     },
     screen::Screen,
@@ -15,7 +16,7 @@ use crate::{
 
 use super::TravelDistance;
 
-#[derive(Resource, Debug)]
+#[derive(Resource, Debug, Default)]
 pub struct Raininess {
     factor: f32,
     time_rain_ends: f32,
@@ -23,14 +24,18 @@ pub struct Raininess {
 
 impl Raininess {
     /// updates the rain status and returns true if the status changed
-    pub fn update(&mut self, distance: &TravelDistance, delta: Range<f32>) -> bool {
-        let mut rng = rand::thread_rng();
+    pub fn update(
+        &mut self,
+        distance: &TravelDistance,
+        delta: Range<f32>,
+        rng: &mut impl Rng,
+    ) -> bool {
         self.factor = (self.factor + rng.gen_range(delta)).clamp(0.0, 1.0);
         let was_raining = self.is_raining();
 
         if self.factor > RAIN_THRESHOLD && self.time_rain_ends < 0.01 {
             // start raining
-            self.time_rain_ends = distance.future_range(RAIN_MIN_DURATION..RAIN_MAX_DURATION);
+            self.time_rain_ends = distance.future_range(RAIN_MIN_DURATION..RAIN_MAX_DURATION, rng);
         // rain for this many seconds
         } else if self.time_rain_ends > 0. && self.time_rain_ends < distance.get() {
             // stop raining
@@ -69,19 +74,13 @@ const RAIN_MIN_GROWTH: f32 = -0.001;
 const RAIN_MAX_GROWTH: f32 = 0.0017;
 
 pub(super) fn plugin(app: &mut App) {
-    app.insert_resource(Raininess {
-        factor: 0.,
-        time_rain_ends: 0.,
-    });
-    app.add_systems(OnEnter(Screen::Playing), (set_up_snow, reset_raininess));
+    app.init_resource::<Raininess>();
+    app.add_systems(NEW_VOYAGE, reset_raininess);
+    app.add_systems(OnEnter(Screen::Playing), set_up_snow);
+    app.add_systems(FixedUpdate, update_raininess.in_set(VoyageSet::Weather));
     app.add_systems(
         Update,
-        (
-            update_raininess,
-            spawn_rain_drops,
-            spawn_snow_flakes,
-            animate_snow_flakes,
-        )
+        (spawn_rain_drops, spawn_snow_flakes, animate_snow_flakes)
             .run_if(in_state(Screen::Playing)),
     );
     app.observe(handle_rain_changed);
@@ -95,8 +94,13 @@ pub fn update_raininess(
     mut commands: Commands,
     distance: Res<TravelDistance>,
     mut raininess: ResMut<Raininess>,
+    mut rng: ResMut<GameRng>,
 ) {
-    if raininess.update(&distance, RAIN_MIN_GROWTH..RAIN_MAX_GROWTH) {
+    if raininess.update(
+        &distance,
+        RAIN_MIN_GROWTH..RAIN_MAX_GROWTH,
+        rng.stream(RngStream::Rain),
+    ) {
         commands.trigger(RainChanged {
             is_raining: raininess.is_raining(),
         });
@@ -197,8 +201,11 @@ fn spawn_rain_drops(
     image_handles: Res<HandleMap<ImageKey>>,
     win_size: Res<WindowSize>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut rng: ResMut<GameRng>,
     mut rains: Query<&mut Rain>,
 ) {
+    let rng = rng.stream(RngStream::Effects);
+
     for mut rain in &mut rains {
         if rain.next_spawn > distance.get() {
             continue;
@@ -207,7 +214,7 @@ fn spawn_rain_drops(
         let texture_atlas_layout = texture_atlas_layouts.add(layout);
         let player_animation = SpriteAnimationPlayer::raindrop();
 
-        let pos = win_size.get_random_position();
+        let pos = win_size.get_random_position(rng);
 
         commands
             .spawn((
@@ -249,6 +256,7 @@ fn spawn_snow_flakes(
     image_handles: Res<HandleMap<ImageKey>>,
     win_size: Res<WindowSize>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut rng: ResMut<GameRng>,
     mut snows: Query<(Entity, &mut Snow)>,
 ) {
     // only snow at southern latitudes
@@ -259,7 +267,7 @@ fn spawn_snow_flakes(
         return;
     }
 
-    let mut rng = rand::thread_rng();
+    let rng = rng.stream(RngStream::Effects);
 
     for (entity, mut snow) in &mut snows {
         if snow.next_spawn > distance.get() {
@@ -268,7 +276,7 @@ fn spawn_snow_flakes(
         let layout = TextureAtlasLayout::from_grid(UVec2::splat(16), 8, 2, None, None);
         let texture_atlas_layout = texture_atlas_layouts.add(layout);
 
-        let pos = win_size.get_random_position();
+        let pos = win_size.get_random_position(rng);
 
         commands
             .spawn((
@@ -302,13 +310,14 @@ fn spawn_snow_flakes(
 fn animate_snow_flakes(
     mut commands: Commands,
     time: Res<Time>,
+    mut rng: ResMut<GameRng>,
     mut snowflakes: Query<(Entity, &mut Transform, &mut Snowflake)>,
 ) {
     if snowflakes.is_empty() {
         return;
     }
 
-    let mut rng = rand::thread_rng();
+    let rng = rng.stream(RngStream::Effects);
 
     for (entity, mut tx, mut snowflake) in &mut snowflakes {
         tx.translation += 30.0 * snowflake.velocity * time.delta_seconds();
@@ -9,6 +9,7 @@ use crate::{
         movement::{
             DespawnWhenOutOfWindow, MoveWithVelocity, WHALE_TRAVEL_SPEED, WINDOW_DESPAWN_BUFFER,
         },
+        rng::{GameRng, RngStream},
         spawn::WindowSize,
     },
     screen::Screen,
@@ -34,8 +35,12 @@ pub(super) fn plugin(app: &mut App) {
     app.observe(spawn_wave);
 }
 
-fn spawn_initial_waves(mut commands: Commands, win_size: Res<WindowSize>) {
-    let mut rng = rand::thread_rng();
+fn spawn_initial_waves(
+    mut commands: Commands,
+    win_size: Res<WindowSize>,
+    mut rng: ResMut<GameRng>,
+) {
+    let rng = rng.stream(RngStream::Effects);
 
     let size = win_size.size();
     let x_range =
@@ -56,8 +61,11 @@ fn spawn_random_waves(
     mut commands: Commands,
     distance: Res<TravelDistance>,
     win_size: Res<WindowSize>,
+    mut rng: ResMut<GameRng>,
     mut next_spawn: Local<f32>,
 ) {
+    let rng = rng.stream(RngStream::Effects);
+
     if distance.get() < 1. {
         *next_spawn = 3.;
     }
@@ -66,7 +74,7 @@ fn spawn_random_waves(
         return;
     }
 
-    *next_spawn = distance.future_range(0.25..1.1);
+    *next_spawn = distance.future_range(0.25..1.1, rng);
 
     let half_size = win_size.half();
     if half_size.length_squared() < 1. {
@@ -75,8 +83,6 @@ fn spawn_random_waves(
         return;
     }
 
-    let mut rng = rand::thread_rng();
-
     let y = -half_size.y - 64.;
     let x = rng.gen_range(
         -half_size.x - 0.5 * WINDOW_DESPAWN_BUFFER..half_size.x + 0.5 * WINDOW_DESPAWN_BUFFER,
@@ -95,6 +101,7 @@ fn spawn_wave(
     mut commands: Commands,
     image_handles: Res<HandleMap<ImageKey>>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut rng: ResMut<GameRng>,
 ) {
     let event = trigger.event();
 
@@ -107,8 +114,9 @@ fn spawn_wave(
         Name::new("Wave"),
         SpriteBundle {
             texture: image_handles[&ImageKey::Features].clone_weak(),
-            transform: Transform::from_translation(Vec3::new(event.x, event.y, 0.0))
-                .with_scale(Vec3::splat(rand::thread_rng().gen_range(0.98..1.2))),
+            transform: Transform::from_translation(Vec3::new(event.x, event.y, 0.0)).with_scale(
+                Vec3::splat(rng.stream(RngStream::Effects).gen_range(0.98..1.2)),
+            ),
             ..Default::default()
         },
         TextureAtlas {
@@ -4,6 +4,7 @@ mod game;
 mod screen;
 mod ui;
 
+use bevoids::boids::BoidLodFocus;
 use bevy::{
     asset::AssetMetaCheck,
     audio::{AudioPlugin, Volume},
@@ -85,5 +86,7 @@ fn spawn_camera(mut commands: Commands) {
         // [ui node outlines](https://bevyengine.org/news/bevy-0-14/#ui-node-outline-gizmos)
         // for debugging. So it's good to have this here for future-proofing.
         IsDefaultUiCamera,
+        // boids near the middle of the screen get the most detailed simulation
+        BoidLodFocus,
     ));
 }
@@ -5,7 +5,9 @@ use bevy::prelude::*;
 
 use super::Screen;
 use crate::{
-    game::assets::{HandleMap, ImageKey, SfxKey, SoundtrackKey},
+    game::assets::{
+        BoidPresetKey, EncounterTableKey, HandleMap, ImageKey, SfxKey, SoundtrackKey, TimelineKey,
+    },
     ui::prelude::*,
 };
 
@@ -31,10 +33,16 @@ fn all_assets_loaded(
     image_handles: Res<HandleMap<ImageKey>>,
     sfx_handles: Res<HandleMap<SfxKey>>,
     soundtrack_handles: Res<HandleMap<SoundtrackKey>>,
+    boid_preset_handles: Res<HandleMap<BoidPresetKey>>,
+    encounter_table_handles: Res<HandleMap<EncounterTableKey>>,
+    timeline_handles: Res<HandleMap<TimelineKey>>,
 ) -> bool {
     image_handles.all_loaded(&asset_server)
         && sfx_handles.all_loaded(&asset_server)
         && soundtrack_handles.all_loaded(&asset_server)
+        && boid_preset_handles.all_loaded(&asset_server)
+        && encounter_table_handles.all_loaded(&asset_server)
+        && timeline_handles.all_loaded(&asset_server)
 }
 
 fn continue_to_title(mut next_screen: ResMut<NextState<Screen>>) {
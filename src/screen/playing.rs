@@ -6,6 +6,7 @@ use super::Screen;
 use crate::game::{
     assets::{SfxKey, SoundtrackKey},
     audio::{sfx::PlaySfx, soundtrack::PlaySoundtrack},
+    rng::GameRng,
     spawn::{level::SpawnLevel, player::WhaleRotation},
 };
 
@@ -20,13 +21,33 @@ pub(super) fn plugin(app: &mut App) {
     );
 }
 
-fn enter_playing(mut commands: Commands, mut whale_rot: ResMut<WhaleRotation>) {
+fn enter_playing(mut commands: Commands, mut whale_rot: ResMut<WhaleRotation>, rng: Res<GameRng>) {
     commands.trigger(SpawnLevel);
     commands.trigger(PlaySoundtrack::Key(SoundtrackKey::Gameplay));
     commands.trigger(PlaySfx::looped(SfxKey::OceanAmbient).with_volume(0.35));
 
     whale_rot.current_rotation = 0.;
     whale_rot.target_rotation = 0.;
+
+    // so the voyage can be replayed, e.g. when reporting a bug
+    commands.spawn((
+        Name::new("Voyage Seed"),
+        TextBundle::from_section(
+            format!("Seed: {}", rng.seed()),
+            TextStyle {
+                font_size: 14.0,
+                color: Color::srgba(1., 1., 1., 0.6),
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            left: Val::Px(8.),
+            bottom: Val::Px(8.),
+            ..default()
+        }),
+        StateScoped(Screen::Playing),
+    ));
 }
 
 fn exit_playing(mut commands: Commands) {
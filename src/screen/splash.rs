@@ -27,6 +27,7 @@ pub(super) fn plugin(app: &mut App) {
             (
                 apply_fade_in_out_to_backgrounds,
                 apply_fade_in_out_to_ui_images,
+                apply_fade_in_out_to_text,
             )
                 .in_set(AppSet::Update),
         ),
@@ -150,6 +151,14 @@ fn apply_fade_in_out_to_ui_images(mut animation_query: Query<(&UiImageFadeInOut,
     }
 }
 
+fn apply_fade_in_out_to_text(mut animation_query: Query<(&UiImageFadeInOut, &mut Text)>) {
+    for (anim, mut text) in &mut animation_query {
+        for section in &mut text.sections {
+            section.style.color.set_alpha(anim.alpha())
+        }
+    }
+}
+
 fn apply_fade_in_out_to_backgrounds(
     mut animation_query: Query<(&UiImageFadeInOut, &mut BackgroundColor)>,
 ) {
@@ -1,13 +1,20 @@
 //! The title screen that appears when the game starts.
 
-use bevy::prelude::*;
-use ui_palette::NODE_BACKGROUND;
+use bevy::{
+    input::{
+        keyboard::{Key, KeyboardInput},
+        ButtonState,
+    },
+    prelude::*,
+};
+use ui_palette::{NODE_BACKGROUND, WHALE_BLUE};
 
 use super::Screen;
 use crate::{
     game::{
         assets::{HandleMap, ImageKey, SoundtrackKey},
         audio::soundtrack::PlaySoundtrack,
+        rng::VoyageSeed,
     },
     ui::prelude::*,
 };
@@ -16,9 +23,16 @@ pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Screen::Title), enter_title);
 
     app.register_type::<TitleAction>();
-    app.add_systems(Update, handle_title_action.run_if(in_state(Screen::Title)));
+    app.add_systems(
+        Update,
+        (handle_title_action, edit_voyage_seed).run_if(in_state(Screen::Title)),
+    );
 }
 
+/// Marks the text showing the seed for the next voyage
+#[derive(Component)]
+struct VoyageSeedText;
+
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
 #[reflect(Component)]
 enum TitleAction {
@@ -32,6 +46,7 @@ enum TitleAction {
 fn enter_title(
     mut commands: Commands,
     image_handles: Res<HandleMap<ImageKey>>,
+    voyage_seed: Res<VoyageSeed>,
     mut clear_colour: ResMut<ClearColor>,
 ) {
     clear_colour.0 = NODE_BACKGROUND;
@@ -44,7 +59,7 @@ fn enter_title(
                 .spawn(NodeBundle {
                     style: Style {
                         width: Val::Percent(100.0),
-                        height: Val::Percent(70.0),
+                        height: Val::Percent(65.0),
                         justify_content: JustifyContent::Center,
                         align_items: AlignItems::Center,
                         flex_direction: FlexDirection::Column,
@@ -86,6 +101,19 @@ fn enter_title(
                         .image_button(image_handles[&ImageKey::ExitButton].clone_weak())
                         .insert(TitleAction::Exit);
                 });
+
+            children.spawn((
+                Name::new("Voyage Seed"),
+                VoyageSeedText,
+                TextBundle::from_section(
+                    voyage_seed_text(&voyage_seed),
+                    TextStyle {
+                        font_size: 18.0,
+                        color: WHALE_BLUE,
+                        ..default()
+                    },
+                ),
+            ));
         });
 
     commands.trigger(PlaySoundtrack::Key(SoundtrackKey::Menu));
@@ -114,3 +142,41 @@ fn handle_title_action(
         }
     }
 }
+
+fn voyage_seed_text(voyage_seed: &VoyageSeed) -> String {
+    match voyage_seed.0 {
+        Some(seed) => format!("Seed: {seed} (backspace to clear)"),
+        None => "Seed: random (type a seed to replay a voyage)".to_owned(),
+    }
+}
+
+/// Lets the player type in the seed of a voyage they want to replay
+fn edit_voyage_seed(
+    mut keys: EventReader<KeyboardInput>,
+    mut voyage_seed: ResMut<VoyageSeed>,
+    mut texts: Query<&mut Text, With<VoyageSeedText>>,
+) {
+    for key in keys.read() {
+        if key.state != ButtonState::Pressed {
+            continue;
+        }
+
+        let seed = voyage_seed.0.unwrap_or(0);
+        voyage_seed.0 = match &key.logical_key {
+            Key::Character(character) => match character.parse::<u64>() {
+                // ignore digits that would overflow the seed
+                Ok(digit) if digit < 10 => seed
+                    .checked_mul(10)
+                    .and_then(|seed| seed.checked_add(digit))
+                    .or(voyage_seed.0),
+                _ => continue,
+            },
+            Key::Backspace => Some(seed / 10).filter(|seed| *seed > 0),
+            _ => continue,
+        };
+
+        for mut text in &mut texts {
+            text.sections[0].value = voyage_seed_text(&voyage_seed);
+        }
+    }
+}